@@ -0,0 +1,230 @@
+//! Named collections of assets, listed in `assets/game.manifest.ron`.
+//!
+//! Every collection is loaded as a labeled [`AssetCollection`], e.g.
+//! `game.manifest.ron#player`, and turned into a resource with
+//! [`FromCollection`](crate::asset_tracking::FromCollection). Assets can be
+//! added or swapped by editing the manifest, without recompiling.
+
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, UntypedAssetId},
+    prelude::*,
+    render::texture::{ImageLoaderSettings, ImageSampler},
+};
+use derive_more::derive::{Display, Error, From};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<AssetManifest>();
+    app.init_asset::<AssetCollection>();
+    app.init_asset_loader::<AssetManifestLoader>();
+}
+
+/// Path of the manifest, relative to the assets folder.
+pub const MANIFEST_PATH: &str = "game.manifest.ron";
+
+/// The manifest file as written in RON.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub collections: BTreeMap<String, CollectionFile>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollectionFile {
+    pub images: BTreeMap<String, ImageFile>,
+    pub audio: BTreeMap<String, String>,
+    pub fonts: BTreeMap<String, String>,
+}
+
+impl CollectionFile {
+    /// Every path in the collection.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.images
+            .values()
+            .map(|image| image.path.as_str())
+            .chain(self.audio.values().map(String::as_str))
+            .chain(self.fonts.values().map(String::as_str))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageFile {
+    pub path: String,
+    #[serde(default)]
+    pub sampler: Sampler,
+}
+
+/// How an image is sampled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sampler {
+    /// Keeps the pixel art crisp.
+    #[default]
+    Nearest,
+    Linear,
+}
+
+/// The loaded manifest. Its collections are available as labeled assets.
+#[derive(Asset, TypePath, Debug)]
+pub struct AssetManifest {
+    /// Only held so the collections stay loaded with the manifest.
+    _collections: HashMap<String, Handle<AssetCollection>>,
+}
+
+/// Handles to the assets of one collection, by name.
+#[derive(Asset, TypePath, Debug, Default)]
+pub struct AssetCollection {
+    name: String,
+    images: HashMap<String, Handle<Image>>,
+    audio: HashMap<String, Handle<AudioSource>>,
+    fonts: HashMap<String, Handle<Font>>,
+}
+
+impl AssetCollection {
+    /// A collection with placeholder handles, to check whether a resource
+    /// can be built from it without loading anything.
+    pub fn unloaded(name: &str, file: &CollectionFile) -> Self {
+        Self {
+            name: name.to_string(),
+            images: file
+                .images
+                .keys()
+                .map(|key| (key.clone(), Handle::default()))
+                .collect(),
+            audio: file
+                .audio
+                .keys()
+                .map(|key| (key.clone(), Handle::default()))
+                .collect(),
+            fonts: file
+                .fonts
+                .keys()
+                .map(|key| (key.clone(), Handle::default()))
+                .collect(),
+        }
+    }
+
+    pub fn image(&self, name: &str) -> Result<Handle<Image>, String> {
+        self.images
+            .get(name)
+            .cloned()
+            .ok_or_else(|| self.missing("image", name))
+    }
+
+    pub fn audio(&self, name: &str) -> Result<Handle<AudioSource>, String> {
+        self.audio
+            .get(name)
+            .cloned()
+            .ok_or_else(|| self.missing("audio", name))
+    }
+
+    pub fn font(&self, name: &str) -> Result<Handle<Font>, String> {
+        self.fonts
+            .get(name)
+            .cloned()
+            .ok_or_else(|| self.missing("font", name))
+    }
+
+    /// Every asset in the collection.
+    pub fn ids(&self) -> Vec<UntypedAssetId> {
+        self.images
+            .values()
+            .map(|handle| handle.id().untyped())
+            .chain(self.audio.values().map(|handle| handle.id().untyped()))
+            .chain(self.fonts.values().map(|handle| handle.id().untyped()))
+            .collect()
+    }
+
+    fn missing(&self, kind: &str, name: &str) -> String {
+        format!(
+            "collection {} in {MANIFEST_PATH} has no {kind} named {name}",
+            self.name
+        )
+    }
+}
+
+#[derive(Default)]
+struct AssetManifestLoader;
+
+#[derive(Debug, Display, Error, From)]
+enum AssetManifestError {
+    #[display("could not read manifest: {_0}")]
+    Io(std::io::Error),
+    #[display("invalid manifest: {_0}")]
+    Ron(ron::error::SpannedError),
+}
+
+impl AssetLoader for AssetManifestLoader {
+    type Asset = AssetManifest;
+    type Settings = ();
+    type Error = AssetManifestError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<AssetManifest, AssetManifestError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: ManifestFile = ron::de::from_bytes(&bytes)?;
+
+        let mut collections = HashMap::new();
+        for (name, collection) in file.collections {
+            let handle = load_context.labeled_asset_scope(name.clone(), |load_context| {
+                load_collection(&name, collection, load_context)
+            });
+            collections.insert(name, handle);
+        }
+        Ok(AssetManifest {
+            _collections: collections,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.ron"]
+    }
+}
+
+/// Start loading the assets of a collection. Their handles become
+/// dependencies of the collection, so it only counts as loaded with them.
+fn load_collection(
+    name: &str,
+    file: CollectionFile,
+    load_context: &mut LoadContext,
+) -> AssetCollection {
+    let images = file
+        .images
+        .into_iter()
+        .map(|(key, image)| {
+            let sampler = image.sampler;
+            let handle = load_context
+                .loader()
+                .with_settings(move |settings: &mut ImageLoaderSettings| {
+                    settings.sampler = match sampler {
+                        Sampler::Nearest => ImageSampler::nearest(),
+                        Sampler::Linear => ImageSampler::linear(),
+                    };
+                })
+                .load(image.path);
+            (key, handle)
+        })
+        .collect();
+    let audio = file
+        .audio
+        .into_iter()
+        .map(|(key, path)| (key, load_context.load(path)))
+        .collect();
+    let fonts = file
+        .fonts
+        .into_iter()
+        .map(|(key, path)| (key, load_context.load(path)))
+        .collect();
+    AssetCollection {
+        name: name.to_string(),
+        images,
+        audio,
+        fonts,
+    }
+}
@@ -2,11 +2,23 @@
 
 use std::collections::VecDeque;
 
-use bevy::prelude::*;
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    prelude::*,
+};
+
+use crate::{
+    asset_manifest::{AssetCollection, AssetManifest, MANIFEST_PATH},
+    definitions::{game_files, Definitions, DefinitionsLoader},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
-    app.add_systems(PreUpdate, load_resource_assets);
+    app.init_resource::<LoadingProgress>();
+    app.add_systems(
+        PreUpdate,
+        (load_resource_assets, update_loading_progress).chain(),
+    );
 }
 
 pub trait LoadResource {
@@ -14,6 +26,24 @@ pub trait LoadResource {
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
     /// exists when the assets are ready.
     fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
+
+    /// This will load the [`Resource`] from its collection in the asset
+    /// manifest. When all assets of the collection have been loaded, it will
+    /// be inserted as a resource.
+    fn load_collection<T: Resource + FromCollection>(&mut self) -> &mut Self;
+
+    /// This will load the [`Resource`] from its definition files. When all
+    /// of them and the assets they refer to have been loaded, they will be
+    /// merged and inserted as a resource.
+    fn load_definitions<T: Definitions>(&mut self) -> &mut Self;
+}
+
+/// A resource made from a named collection in the asset manifest.
+pub trait FromCollection: Sized {
+    /// Name of the collection in the manifest.
+    const COLLECTION: &'static str;
+
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String>;
 }
 
 impl LoadResource for App {
@@ -21,44 +51,203 @@ impl LoadResource for App {
         self.init_asset::<T>();
         let world = self.world_mut();
         let value = T::from_world(world);
+        let mut dependencies = Vec::new();
+        value.visit_dependencies(&mut |id| dependencies.push(id));
         let assets = world.resource::<AssetServer>();
         let handle = assets.add(value);
         let mut handles = world.resource_mut::<ResourceHandles>();
-        handles
-            .waiting
-            .push_back((handle.untyped(), |world, handle| {
+        handles.waiting.push_back(WaitingResource {
+            handles: vec![handle.untyped()],
+            dependencies,
+            insert: |world, handles| {
                 let assets = world.resource::<Assets<T>>();
-                if let Some(value) = assets.get(handle.id().typed::<T>()) {
+                if let Some(value) = assets.get(handles[0].id().typed::<T>()) {
                     world.insert_resource(value.clone());
                 }
-            }));
+                Ok(())
+            },
+            collection: false,
+        });
+        self
+    }
+
+    fn load_collection<T: Resource + FromCollection>(&mut self) -> &mut Self {
+        let world = self.world_mut();
+        let assets = world.resource::<AssetServer>();
+        // Keep the manifest loaded, and report if it fails.
+        let manifest = assets.load::<AssetManifest>(MANIFEST_PATH);
+        let handle = assets.load::<AssetCollection>(format!("{MANIFEST_PATH}#{}", T::COLLECTION));
+        let mut handles = world.resource_mut::<ResourceHandles>();
+        // The assets in the collection are only known once the manifest is
+        // loaded, until then count the manifest itself.
+        handles.waiting.push_back(WaitingResource {
+            dependencies: vec![manifest.id().untyped(), handle.id().untyped()],
+            handles: vec![handle.untyped()],
+            insert: |world, handles| {
+                let collections = world.resource::<Assets<AssetCollection>>();
+                if let Some(collection) =
+                    collections.get(handles[0].id().typed::<AssetCollection>())
+                {
+                    let value = T::from_collection(collection)?;
+                    world.insert_resource(value);
+                }
+                Ok(())
+            },
+            collection: true,
+        });
+        handles.finished.push(manifest.untyped());
+        self
+    }
+
+    fn load_definitions<T: Definitions>(&mut self) -> &mut Self {
+        self.init_asset::<T>();
+        self.register_asset_loader(DefinitionsLoader::<T>::default());
+        let files = game_files::<T>(self.world());
+        let world = self.world_mut();
+        let assets = world.resource::<AssetServer>();
+        let handles = files
+            .into_iter()
+            .map(|file| assets.load::<T>(file).untyped())
+            .collect::<Vec<_>>();
+        let mut resource_handles = world.resource_mut::<ResourceHandles>();
+        resource_handles.waiting.push_back(WaitingResource {
+            dependencies: handles.iter().map(UntypedHandle::id).collect(),
+            handles,
+            insert: |world, handles| {
+                let files = world.resource::<Assets<T>>();
+                let mut value = T::default();
+                for handle in handles {
+                    if let Some(file) = files.get(handle.id().typed::<T>()) {
+                        value.extend(file.clone());
+                    }
+                }
+                world.insert_resource(value);
+                Ok(())
+            },
+            collection: false,
+        });
         self
     }
 }
 
-/// A function that inserts a loaded resource.
-type InsertLoadedResource = fn(&mut World, &UntypedHandle);
+/// A function that inserts a loaded resource, or explains why it can't.
+type InsertLoadedResource = fn(&mut World, &[UntypedHandle]) -> Result<(), String>;
+
+struct WaitingResource {
+    /// The resource is made from these once they are loaded.
+    handles: Vec<UntypedHandle>,
+    dependencies: Vec<UntypedAssetId>,
+    insert: InsertLoadedResource,
+    /// Whether the only handle is an [`AssetCollection`], whose dependencies are
+    /// only known once it is loaded.
+    collection: bool,
+}
 
 #[derive(Resource, Default)]
-struct ResourceHandles {
+pub struct ResourceHandles {
     // Use a queue for waiting assets so they can be cycled through and moved to
     // `finished` one at a time.
-    waiting: VecDeque<(UntypedHandle, InsertLoadedResource)>,
+    waiting: VecDeque<WaitingResource>,
     finished: Vec<UntypedHandle>,
+    /// Number of dependencies of the finished resources.
+    finished_dependencies: usize,
+    /// Resources that could not be made from their loaded assets.
+    errors: Vec<String>,
+}
+
+impl ResourceHandles {
+    /// Whether every resource added with [`LoadResource`] has been inserted.
+    pub fn is_all_done(&self) -> bool {
+        self.waiting.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// How far loading the resources added with [`LoadResource`] is.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct LoadingProgress {
+    /// Number of assets that are loaded, out of [`Self::total`].
+    pub loaded: usize,
+    pub total: usize,
+    /// Paths of the assets that failed to load, and resources that could not
+    /// be made from their collection. These resources will never be inserted.
+    pub failed: Vec<String>,
+}
+
+impl LoadingProgress {
+    /// The loaded fraction, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
 }
 
 fn load_resource_assets(world: &mut World) {
     world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
         world.resource_scope(|world, assets: Mut<AssetServer>| {
             for _ in 0..resource_handles.waiting.len() {
-                let (handle, insert_fn) = resource_handles.waiting.pop_front().unwrap();
-                if assets.is_loaded_with_dependencies(&handle) {
-                    insert_fn(world, &handle);
-                    resource_handles.finished.push(handle);
+                let mut waiting = resource_handles.waiting.pop_front().unwrap();
+                if waiting.collection {
+                    let collections = world.resource::<Assets<AssetCollection>>();
+                    if let Some(collection) =
+                        collections.get(waiting.handles[0].id().typed::<AssetCollection>())
+                    {
+                        waiting.dependencies = collection.ids();
+                        waiting.collection = false;
+                    }
+                }
+                if waiting
+                    .handles
+                    .iter()
+                    .all(|handle| assets.is_loaded_with_dependencies(handle))
+                {
+                    if let Err(error) = (waiting.insert)(world, &waiting.handles) {
+                        resource_handles.errors.push(error);
+                    }
+                    resource_handles.finished_dependencies += waiting.dependencies.len();
+                    resource_handles.finished.extend(waiting.handles);
                 } else {
-                    resource_handles.waiting.push_back((handle, insert_fn));
+                    resource_handles.waiting.push_back(waiting);
                 }
             }
         });
     });
 }
+
+fn update_loading_progress(
+    resource_handles: Res<ResourceHandles>,
+    assets: Res<AssetServer>,
+    mut progress: ResMut<LoadingProgress>,
+) {
+    let mut new_progress = LoadingProgress {
+        loaded: resource_handles.finished_dependencies,
+        total: resource_handles.finished_dependencies,
+        failed: resource_handles.errors.clone(),
+    };
+    for waiting in &resource_handles.waiting {
+        for &id in &waiting.dependencies {
+            new_progress.total += 1;
+            if assets.is_loaded_with_dependencies(id) {
+                new_progress.loaded += 1;
+            } else if matches!(assets.get_load_state(id), Some(LoadState::Failed(_)))
+                // The asset itself may be fine while something it depends on
+                // failed, which would leave it loading forever.
+                || matches!(
+                    assets.get_recursive_dependency_load_state(id),
+                    Some(RecursiveDependencyLoadState::Failed)
+                )
+            {
+                let path = assets
+                    .get_path(id)
+                    .map_or_else(|| format!("{id:?}"), |path| path.to_string());
+                if !new_progress.failed.contains(&path) {
+                    new_progress.failed.push(path);
+                }
+            }
+        }
+    }
+    // Only trigger change detection when something happened.
+    progress.set_if_neq(new_progress);
+}
@@ -0,0 +1,75 @@
+//! Lists the UI text and dialogue lines that are not translated yet as a CSV
+//! for translators. Run from the repository root:
+//!
+//! ```sh
+//! cargo run --bin extract_strings -- [--language de-DE] [--out untranslated.csv]
+//! ```
+//!
+//! Without `--language`, every language but English is listed.
+
+use std::{io, path::Path, process::ExitCode};
+
+use thanks_wife::localization::{find_untranslated, Language, Untranslated};
+
+fn main() -> ExitCode {
+    let mut languages = Vec::new();
+    let mut out = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--language" => {
+                let code = args.next().unwrap_or_default();
+                let Some(language) = Language::ALL
+                    .into_iter()
+                    .find(|language| language.code() == code)
+                else {
+                    eprintln!("unknown language {code:?}");
+                    return ExitCode::FAILURE;
+                };
+                languages.push(language);
+            }
+            "--out" => out = args.next(),
+            _ => {
+                eprintln!("usage: extract_strings [--language <code>] [--out <file>]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if languages.is_empty() {
+        languages.extend(
+            Language::ALL
+                .into_iter()
+                .filter(|&language| language != Language::BASE),
+        );
+    }
+
+    let mut untranslated = Vec::new();
+    for language in languages {
+        match find_untranslated(Path::new("assets"), language) {
+            Ok(strings) => untranslated.extend(strings),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let result = match &out {
+        Some(path) => csv::Writer::from_path(path).and_then(|writer| write(writer, &untranslated)),
+        None => write(csv::Writer::from_writer(io::stdout()), &untranslated),
+    };
+    if let Err(error) = result {
+        eprintln!("could not write the CSV: {error}");
+        return ExitCode::FAILURE;
+    }
+    eprintln!("{} untranslated strings", untranslated.len());
+    ExitCode::SUCCESS
+}
+
+fn write<W: io::Write>(mut writer: csv::Writer<W>, strings: &[Untranslated]) -> csv::Result<()> {
+    for string in strings {
+        writer.serialize(string)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Checks that every ending can be reached and that the player can't get
+//! stuck. Run from the repository root:
+//!
+//! ```sh
+//! cargo run --bin puzzle_check -- [--dot puzzle.dot]
+//! ```
+
+use std::{path::Path, process::ExitCode};
+
+use thanks_wife::puzzle::Puzzle;
+
+fn main() -> ExitCode {
+    let mut dot_file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dot" => dot_file = args.next(),
+            _ => eprintln!("ignoring unknown argument {arg}"),
+        }
+    }
+
+    let puzzle = match Puzzle::load(Path::new("assets")) {
+        Ok(puzzle) => puzzle,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(dot_file) = dot_file {
+        if let Err(error) = std::fs::write(&dot_file, puzzle.to_dot()) {
+            eprintln!("could not write {dot_file}: {error}");
+            return ExitCode::FAILURE;
+        }
+        println!("wrote puzzle graph to {dot_file}");
+    }
+
+    let report = puzzle.check();
+    println!("explored {} states", report.states);
+    for (ending, path) in &report.reachable_endings {
+        println!("ending {ending} reachable: {}", path.join(" -> "));
+    }
+    for ending in &report.unreachable_endings {
+        println!("ending {ending} is unreachable");
+    }
+    for path in &report.soft_locks {
+        println!("soft-lock: {}", path.join(" -> "));
+    }
+    for (error, path) in &report.errors {
+        println!("error: {error} after {}", path.join(" -> "));
+    }
+
+    if report.is_ok() {
+        println!("puzzle is solvable");
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,22 @@
+//! Checks the dialogue and assets for mistakes. Run from the repository root:
+//!
+//! ```sh
+//! cargo run --bin validate_content
+//! ```
+
+use std::{path::Path, process::ExitCode};
+
+use thanks_wife::validate::validate;
+
+fn main() -> ExitCode {
+    let problems = validate(Path::new("assets"));
+    if problems.is_empty() {
+        println!("content is valid");
+        return ExitCode::SUCCESS;
+    }
+    for problem in &problems {
+        println!("{problem}");
+    }
+    println!("found {} problems", problems.len());
+    ExitCode::FAILURE
+}
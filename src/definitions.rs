@@ -0,0 +1,161 @@
+//! Content that is defined in RON files instead of code, so that mods can add
+//! to it, like the items and the area layouts.
+//!
+//! Every file with the right extension in a folder of the assets is loaded
+//! and merged into one resource with
+//! [`LoadResource::load_definitions`](crate::asset_tracking::LoadResource::load_definitions).
+//! Native builds also pick up the files in that folder of every mod, while
+//! the web only loads the files of the base game that are listed in code.
+
+use std::{fs, io, marker::PhantomData, path::Path};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use derive_more::derive::{Display, Error, From};
+use serde::de::DeserializeOwned;
+
+#[cfg(not(target_family = "wasm"))]
+use crate::mods::{assets_path, Mods};
+
+/// A resource that is merged from definition files.
+pub trait Definitions: Resource + Asset + Clone + Default + DeserializeOwned {
+    /// The folder the files are in, relative to the assets folder.
+    const FOLDER: &'static str;
+    /// The extension of the files, e.g. `items.ron`.
+    const EXTENSION: &'static str;
+    /// The files of the base game, relative to the assets folder, for
+    /// platforms that can't list folders.
+    const FILES: &'static [&'static str];
+
+    /// Add the definitions of another file, which is loaded later.
+    fn extend(&mut self, other: Self);
+
+    /// Start loading the assets the definitions refer to. They become
+    /// dependencies of the file, so it only counts as loaded with them.
+    fn load_dependencies(&mut self, _load_context: &mut LoadContext) {}
+}
+
+/// All definition files in the folder of `root`, relative to it.
+pub fn discover_files<T: Definitions>(root: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(root.join(T::FOLDER))? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            if name.ends_with(&format!(".{}", T::EXTENSION)) {
+                files.push(format!("{}/{name}", T::FOLDER));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read every definition file in `root` on its own, for tools.
+pub fn read_files<T: Definitions>(root: &Path) -> Result<Vec<(String, T)>, String> {
+    let files = match discover_files::<T>(root) {
+        Ok(files) => files,
+        // Mods don't need to define anything.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(format!("could not list {}: {error}", T::FOLDER)),
+    };
+    files
+        .into_iter()
+        .map(|file| {
+            let source = fs::read_to_string(root.join(&file))
+                .map_err(|error| format!("could not read {file}: {error}"))?;
+            let definitions =
+                ron::from_str(&source).map_err(|error| format!("invalid {file}: {error}"))?;
+            Ok((file, definitions))
+        })
+        .collect()
+}
+
+/// Read and merge all definition files in `root`, for tools.
+pub fn read_definitions<T: Definitions>(root: &Path) -> Result<T, String> {
+    let mut definitions = T::default();
+    for (_, file) in read_files::<T>(root)? {
+        definitions.extend(file);
+    }
+    Ok(definitions)
+}
+
+/// The definition files the game loads, in the order they are merged: the
+/// base game's first, then the ones of every mod. Files with the same name
+/// replace the base game's.
+#[cfg(not(target_family = "wasm"))]
+pub fn game_files<T: Definitions>(world: &World) -> Vec<String> {
+    let mut files = match discover_files::<T>(&assets_path()) {
+        Ok(files) => files,
+        Err(error) => {
+            warn!(
+                "could not list {}, using the built-in list: {error}",
+                T::FOLDER
+            );
+            T::FILES.iter().map(ToString::to_string).collect()
+        }
+    };
+    for info in world
+        .get_resource::<Mods>()
+        .iter()
+        .flat_map(|mods| &mods.mods)
+    {
+        for file in discover_files::<T>(&info.path).unwrap_or_default() {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+#[cfg(target_family = "wasm")]
+pub fn game_files<T: Definitions>(_world: &World) -> Vec<String> {
+    T::FILES.iter().map(ToString::to_string).collect()
+}
+
+pub struct DefinitionsLoader<T> {
+    extensions: [&'static str; 1],
+    marker: PhantomData<T>,
+}
+
+impl<T: Definitions> Default for DefinitionsLoader<T> {
+    fn default() -> Self {
+        Self {
+            extensions: [T::EXTENSION],
+            marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Display, Error, From)]
+pub enum DefinitionsError {
+    #[display("could not read definitions: {_0}")]
+    Io(std::io::Error),
+    #[display("invalid definitions: {_0}")]
+    Ron(ron::error::SpannedError),
+}
+
+impl<T: Definitions> AssetLoader for DefinitionsLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = DefinitionsError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<T, DefinitionsError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut definitions: T = ron::de::from_bytes(&bytes)?;
+        definitions.load_dependencies(load_context);
+        Ok(definitions)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+}
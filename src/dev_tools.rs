@@ -10,7 +10,10 @@ use bevy::{
 };
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-use crate::screens::{Area, Screen};
+use crate::{
+    game::movement::ActionsFrozen,
+    screens::{Area, Screen},
+};
 
 const INSPECTOR_TOGGLE_KEY: KeyCode = KeyCode::Backquote;
 
@@ -27,6 +30,15 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         toggle_debug_ui.run_if(input_just_pressed(UI_TOGGLE_KEY)),
     );
+
+    app.add_systems(Startup, spawn_freeze_overlay);
+    app.add_systems(
+        Update,
+        (
+            toggle_freeze_overlay.run_if(input_just_pressed(FREEZE_OVERLAY_TOGGLE_KEY)),
+            update_freeze_overlay.run_if(resource_changed::<ActionsFrozen>),
+        ),
+    );
 }
 
 const UI_TOGGLE_KEY: KeyCode = KeyCode::KeyU;
@@ -34,3 +46,55 @@ const UI_TOGGLE_KEY: KeyCode = KeyCode::KeyU;
 fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
     options.toggle();
 }
+
+const FREEZE_OVERLAY_TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+/// Lists the reasons player actions are currently frozen.
+#[derive(Component)]
+struct FreezeOverlay;
+
+fn spawn_freeze_overlay(mut commands: Commands) {
+    let mut text = TextBundle::from_section(
+        "",
+        TextStyle {
+            font_size: 18.0,
+            ..default()
+        },
+    )
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(5.0),
+        right: Val::Px(5.0),
+        ..default()
+    });
+    text.visibility = Visibility::Hidden;
+    commands.spawn((Name::new("Freeze Overlay"), FreezeOverlay, text));
+}
+
+fn toggle_freeze_overlay(mut overlay: Query<&mut Visibility, With<FreezeOverlay>>) {
+    for mut visibility in &mut overlay {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_freeze_overlay(
+    actions_frozen: Res<ActionsFrozen>,
+    mut overlay: Query<&mut Text, With<FreezeOverlay>>,
+) {
+    let reasons = actions_frozen
+        .freezes()
+        .iter()
+        .map(|freeze| format!("{:?} ({})", freeze.reason, freeze.owner))
+        .collect::<Vec<_>>();
+    let value = if reasons.is_empty() {
+        "Not frozen".to_string()
+    } else {
+        format!("Frozen: {}", reasons.join(", "))
+    };
+    for mut text in &mut overlay {
+        text.sections[0].value.clone_from(&value);
+    }
+}
@@ -1,48 +1,196 @@
+use std::{fs, io, path::Path};
+
 use bevy::prelude::*;
+#[cfg(not(target_family = "wasm"))]
+use bevy_yarnspinner::prelude::YarnFile;
 use bevy_yarnspinner::{
     events::DialogueCompleteEvent,
-    prelude::{DialogueRunner, YarnFileSource, YarnProject, YarnSpinnerPlugin},
+    prelude::{
+        DevelopmentFileGeneration, DialogueRunner, Localization as YarnLocalization, Localizations,
+        YarnFileSource, YarnProject, YarnSpinnerPlugin,
+    },
 };
 use bevy_yarnspinner_example_dialogue_view::ExampleYarnSpinnerDialogueViewPlugin;
 
+#[cfg(not(target_family = "wasm"))]
+use crate::mods::{assets_path, Mods};
 use crate::{
     audio::SoundEffect,
     game::{
+        achievements::{Achievement, UnlockAchievement},
         dino::SpawnDino,
-        inventory::{Inventory, Item},
+        ending::{Ending, ReachEnding},
+        inventory::{Inventory, ItemDefinitions, ItemDropped},
+        journal::{Journal, Objective},
         level::Level,
-        movement::ActionsFrozen,
+        movement::{ActionsFrozen, FreezeReason},
+        paper::{normalize_text, PlayerName},
         player::{AutoRunner, Player, PlayerAssets},
+        speech::{find_speaker, Say},
     },
+    localization::{Language, LanguageSettings, Localization},
     screens::Screen,
+    theme::prelude::*,
+    yarn_script::Program,
 };
 
+/// The folder with all dialogue, relative to the assets folder.
+const DIALOGUE_FOLDER: &str = "dialogue";
+
+/// The dialogue files for platforms that can't list folders.
+const DIALOGUE_MANIFEST: &str = include_str!("../assets/dialogue/manifest.ron");
+
+/// Nodes started by name from Rust, besides the ones of the
+/// [recipes](crate::game::recipe) and the ones started by trigger zones.
+pub const STARTED_NODES: &[&str] = &["Intro", "Wife"];
+
+/// Sounds that can be played with `<<play_sound>>`.
+pub const SOUNDS: &[&str] = &["vine_boom", "uh_oh", "trophy_wife", "wife_hm"];
+
+/// Directions that can be passed to `<<player_run>>`.
+pub const DIRECTIONS: &[&str] = &["left", "right"];
+
+/// The dialogue files listed in `dialogue/manifest.ron`, relative to the
+/// assets folder.
+pub fn manifest_yarn_files() -> Vec<String> {
+    let names: Vec<String> =
+        ron::from_str(DIALOGUE_MANIFEST).expect("dialogue manifest should be valid");
+    names
+        .into_iter()
+        .map(|name| format!("{DIALOGUE_FOLDER}/{name}"))
+        .collect()
+}
+
+/// All `.yarn` files in the dialogue folder of `assets`, relative to it.
+pub fn discover_yarn_files(assets: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(assets.join(DIALOGUE_FOLDER))? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "yarn")
+        {
+            if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                files.push(format!("{DIALOGUE_FOLDER}/{name}"));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Compile all dialogue files in `assets`, for checking them outside of the
+/// game.
+pub fn load_yarn_files(assets: &Path) -> Result<Program, String> {
+    let files = discover_yarn_files(assets)
+        .map_err(|error| format!("could not list {DIALOGUE_FOLDER}: {error}"))?;
+    let mut sources = Vec::new();
+    for file in files {
+        let source = fs::read_to_string(assets.join(&file))
+            .map_err(|error| format!("could not read {file}: {error}"))?;
+        sources.push((file, source));
+    }
+    Program::compile(sources)
+}
+
+/// The dialogue files the game loads. Native builds pick up every file in the
+/// folder and the folders of mods, so new files only need to be added to the
+/// manifest for the web.
+#[cfg(not(target_family = "wasm"))]
+fn yarn_files(mods: Option<&Mods>) -> Vec<String> {
+    let mut files = match discover_yarn_files(&assets_path()) {
+        Ok(files) => files,
+        Err(error) => {
+            warn!("could not list dialogue files, using the manifest: {error}");
+            manifest_yarn_files()
+        }
+    };
+    for info in mods.iter().flat_map(|mods| &mods.mods) {
+        for file in discover_yarn_files(&info.path).unwrap_or_default() {
+            // Files with the same name replace the base game's.
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
 pub(super) fn plugin(app: &mut App) {
+    #[cfg(not(target_family = "wasm"))]
+    let files = yarn_files(app.world().get_resource::<Mods>());
+    #[cfg(target_family = "wasm")]
+    let files = manifest_yarn_files();
     app.add_plugins((
-        YarnSpinnerPlugin::with_yarn_sources(vec![
-            YarnFileSource::file("dialogue/intro.yarn"),
-            YarnFileSource::file("dialogue/wife.yarn"),
-            YarnFileSource::file("dialogue/knife.yarn"),
-            YarnFileSource::file("dialogue/papyrus.yarn"),
-            YarnFileSource::file("dialogue/papyrus_strips.yarn"),
-            YarnFileSource::file("dialogue/woven_papyrus.yarn"),
-            YarnFileSource::file("dialogue/paper.yarn"),
-            YarnFileSource::file("dialogue/dino.yarn"),
-            YarnFileSource::file("dialogue/banan.yarn"),
-            YarnFileSource::file("dialogue/fire.yarn"),
-        ]),
+        YarnSpinnerPlugin::with_yarn_sources(files.into_iter().map(YarnFileSource::file))
+            .with_localizations(localizations())
+            .with_development_file_generation(DevelopmentFileGeneration::None),
         ExampleYarnSpinnerDialogueViewPlugin::new(),
     ));
     app.add_systems(OnEnter(Screen::Gameplay), spawn_dialogue_runner);
     app.add_systems(Update, unfreeze_after_dialog);
+
+    // Yarn Spinner reloads changed files when hot reloading is enabled. Check
+    // them as well, so that mistakes like duplicate nodes are reported with
+    // where both definitions are.
+    #[cfg(not(target_family = "wasm"))]
+    {
+        app.add_systems(Startup, check_dialogue);
+        app.add_systems(Update, check_reloaded_dialogue);
+    }
+}
+
+/// The dialogue is translated with the string tables next to it. They are
+/// only read, new lines are listed with `cargo run --bin extract_strings`.
+fn localizations() -> Localizations {
+    let localization = |language: Language| {
+        YarnLocalization::with_language(language.code())
+            .with_strings_file(language.dialogue_strings_path())
+    };
+    Localizations {
+        base_localization: localization(Language::BASE),
+        translations: Language::ALL
+            .into_iter()
+            .filter(|&language| language != Language::BASE)
+            .map(localization)
+            .collect(),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn check_dialogue() {
+    if let Err(error) = load_yarn_files(&assets_path()) {
+        error!("invalid dialogue: {error}");
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn check_reloaded_dialogue(mut events: EventReader<AssetEvent<YarnFile>>) {
+    let modified = events
+        .read()
+        .filter(|event| matches!(event, AssetEvent::Modified { .. }))
+        .count();
+    if modified > 0 {
+        check_dialogue();
+    }
 }
 
 fn spawn_dialogue_runner(
     mut commands: Commands,
     project: Res<YarnProject>,
+    language_settings: Res<LanguageSettings>,
+    player_name: Res<PlayerName>,
     mut actions_frozen: ResMut<ActionsFrozen>,
 ) {
     let mut dialogue_runner = project.create_dialogue_runner();
+    dialogue_runner.set_language(language_settings.language.code());
+    dialogue_runner
+        .variable_storage_mut()
+        .set(
+            "$player_name".to_string(),
+            normalize_text(&player_name.name).into(),
+        )
+        .unwrap();
     dialogue_runner
         .commands_mut()
         .add_command("inventory_convert", inventory_convert)
@@ -51,6 +199,11 @@ fn spawn_dialogue_runner(
         .add_command("spawn_dino", spawn_dino)
         .add_command("player_run", player_run)
         .add_command("play_sound", play_sound)
+        .add_command("say", say)
+        .add_command("toast", toast)
+        .add_command("objective_add", objective_add)
+        .add_command("objective_complete", objective_complete)
+        .add_command("unlock_achievement", unlock_achievement)
         .add_command("end_game", end_game);
 
     fn inventory_convert(
@@ -59,14 +212,28 @@ fn spawn_dialogue_runner(
         mut dialogue_runner: Query<&mut DialogueRunner>,
         mut inventory: ResMut<Inventory>,
         player_assets: Res<PlayerAssets>,
+        items: Res<ItemDefinitions>,
+        localization: Res<Localization>,
     ) {
+        let Some(item) = items.find(&to) else {
+            warn!("unknown item {to} to convert {from} into");
+            return;
+        };
         let index = inventory
             .items
             .iter()
             .position(|x| *x.to_string() == from)
             .unwrap();
-        inventory.items.remove(index);
-        inventory.items.push(Item::from(to.as_str()));
+        let converted = inventory.items.remove(index);
+        inventory.items.push(item.clone());
+        commands.trigger(
+            Toast::new(format!(
+                "{} -> {}",
+                localization.get(items.name(&converted)),
+                localization.get(items.name(&item))
+            ))
+            .with_icon(items.image(&item)),
+        );
 
         let mut dialogue_runner = dialogue_runner
             .get_single_mut()
@@ -86,14 +253,22 @@ fn spawn_dialogue_runner(
         ));
     }
 
-    fn level_convert(In((from, to)): In<(String, String)>, mut level: ResMut<Level>) {
+    fn level_convert(
+        In((from, to)): In<(String, String)>,
+        mut level: ResMut<Level>,
+        items: Res<ItemDefinitions>,
+    ) {
+        let Some(item) = items.find(&to) else {
+            warn!("unknown item {to} to convert {from} into");
+            return;
+        };
         let index = level
             .items
             .iter()
             .position(|x| *x.to_string() == from)
             .unwrap();
         level.items.remove(index);
-        level.items.push(Item::from(to.as_str()));
+        level.items.push(item);
     }
 
     fn drop(
@@ -110,7 +285,8 @@ fn spawn_dialogue_runner(
             .position(|x| *x.to_string() == item)
             .unwrap();
         let item = inventory.items.remove(index);
-        level.items.push(item);
+        level.items.push(item.clone());
+        commands.trigger(ItemDropped(item.clone()));
 
         let mut dialogue_runner = dialogue_runner
             .get_single_mut()
@@ -133,25 +309,74 @@ fn spawn_dialogue_runner(
         In((direction, end_position)): In<(String, f32)>,
         mut commands: Commands,
         player: Query<Entity, With<Player>>,
+        mut actions_frozen: ResMut<ActionsFrozen>,
     ) {
         let intent = match direction.as_str() {
             "left" => Vec2::new(-1.0, 0.0),
             "right" => Vec2::new(1.0, 0.0),
-            _ => panic!("unknown direction {direction}"),
+            _ => {
+                warn!("unknown direction {direction} to run in");
+                return;
+            }
         };
         let entity = player.get_single().expect("exactly one player");
         commands.entity(entity).insert(AutoRunner {
             end_position,
             intent,
         });
+        actions_frozen.freeze(FreezeReason::Cutscene, entity);
     }
 
     fn spawn_dino(In(()): In<()>, mut commands: Commands) {
         commands.trigger(SpawnDino);
     }
 
-    fn end_game(In(()): In<()>, mut next_state: ResMut<NextState<Screen>>) {
-        next_state.set(Screen::End);
+    fn say(
+        In((speaker, text)): In<(String, String)>,
+        mut commands: Commands,
+        names: Query<(Entity, &Name)>,
+    ) {
+        let Some(entity) = find_speaker(&names, &speaker) else {
+            warn!("no speaker named {speaker} to say \"{text}\"");
+            return;
+        };
+        commands.trigger_targets(Say::new(text), entity);
+    }
+
+    fn toast(In(text): In<String>, mut commands: Commands) {
+        commands.trigger(Toast::new(text));
+    }
+
+    fn objective_add(In(id): In<String>, mut journal: ResMut<Journal>) {
+        let Some(objective) = Objective::from_id(&id) else {
+            warn!("unknown objective {id}");
+            return;
+        };
+        journal.add(objective);
+    }
+
+    fn objective_complete(In(id): In<String>, mut journal: ResMut<Journal>) {
+        let Some(objective) = Objective::from_id(&id) else {
+            warn!("unknown objective {id}");
+            return;
+        };
+        journal.complete(objective);
+    }
+
+    fn unlock_achievement(In(id): In<String>, mut commands: Commands) {
+        let Some(achievement) = Achievement::from_id(&id) else {
+            warn!("unknown achievement {id}");
+            return;
+        };
+        commands.trigger(UnlockAchievement(achievement));
+    }
+
+    fn end_game(In(id): In<String>, mut commands: Commands) {
+        let Some(ending) = Ending::from_id(&id) else {
+            warn!("unknown ending {id}");
+            return;
+        };
+        commands.trigger(ReachEnding(ending));
     }
 
     fn play_sound(In(name): In<String>, mut commands: Commands, player_assets: Res<PlayerAssets>) {
@@ -160,7 +385,10 @@ fn spawn_dialogue_runner(
             "uh_oh" => player_assets.uh_oh.clone(),
             "trophy_wife" => player_assets.trophy_wife.clone(),
             "wife_hm" => player_assets.wife_hm.clone(),
-            _ => panic!("unknown sound {name}"),
+            _ => {
+                warn!("unknown sound {name}");
+                return;
+            }
         };
         commands.spawn((
             AudioBundle {
@@ -173,15 +401,18 @@ fn spawn_dialogue_runner(
     }
 
     dialogue_runner.start_node("Intro");
-    commands.spawn((dialogue_runner, StateScoped(Screen::Gameplay)));
-    actions_frozen.freeze();
+    let runner = commands
+        .spawn((dialogue_runner, StateScoped(Screen::Gameplay)))
+        .id();
+    actions_frozen.freeze(FreezeReason::Dialogue, runner);
 }
 
+/// Only lift the freeze of the runner that finished, in case there are others.
 fn unfreeze_after_dialog(
     mut dialogue_complete_event: EventReader<DialogueCompleteEvent>,
     mut freeze: ResMut<ActionsFrozen>,
 ) {
-    for _event in dialogue_complete_event.read() {
-        freeze.unfreeze();
+    for event in dialogue_complete_event.read() {
+        freeze.unfreeze(FreezeReason::Dialogue, event.source);
     }
 }
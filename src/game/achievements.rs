@@ -0,0 +1,210 @@
+//! Achievements that stay unlocked across runs. They are listed on the
+//! achievements screen, and a [`Toast`] pops up when one is unlocked.
+//!
+//! Dialogue unlocks them with `<<unlock_achievement>>`, the rest is unlocked
+//! by endings, fast runs and the rubber ducks hidden in the areas.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ducks::ducky_atlas_layout,
+    ending::{Ending, ReachEnding, UnlockedEndings},
+    level::LevelAssets,
+    stats::RunStats,
+};
+use crate::{
+    localization::Localization,
+    persistence::{PersistResource, Persistent},
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<UnlockedAchievements>();
+    app.persist_resource::<UnlockedAchievements>();
+    app.observe(unlock_achievement);
+    app.observe(unlock_ending_achievements);
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    GoodEnding,
+    StabEnding,
+    DinoEnding,
+    AllEndings,
+    SparedWife,
+    QuickHunt,
+    FasterThanDog,
+    FirstDuck,
+    AllDucks,
+}
+
+/// Good endings reached faster than this unlock the achievement.
+const SPEEDRUN_ACHIEVEMENTS: [(Achievement, Duration); 2] = [
+    (Achievement::QuickHunt, Duration::from_secs(5 * 60)),
+    (Achievement::FasterThanDog, Duration::from_secs(2 * 60)),
+];
+
+impl Achievement {
+    pub const ALL: [Achievement; 9] = [
+        Achievement::GoodEnding,
+        Achievement::StabEnding,
+        Achievement::DinoEnding,
+        Achievement::AllEndings,
+        Achievement::SparedWife,
+        Achievement::QuickHunt,
+        Achievement::FasterThanDog,
+        Achievement::FirstDuck,
+        Achievement::AllDucks,
+    ];
+
+    /// The id the `unlock_achievement` Yarn command uses.
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::GoodEnding => "good_ending",
+            Achievement::StabEnding => "stab_ending",
+            Achievement::DinoEnding => "dino_ending",
+            Achievement::AllEndings => "all_endings",
+            Achievement::SparedWife => "spared_wife",
+            Achievement::QuickHunt => "quick_hunt",
+            Achievement::FasterThanDog => "faster_than_dog",
+            Achievement::FirstDuck => "first_duck",
+            Achievement::AllDucks => "all_ducks",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Achievement::ALL
+            .into_iter()
+            .find(|achievement| achievement.id() == id)
+    }
+
+    /// The achievement for reaching an ending. Every variant of the good
+    /// ending counts for the same one.
+    pub fn for_ending(ending: Ending) -> Self {
+        match ending {
+            Ending::Good | Ending::Love | Ending::Portrait | Ending::Name | Ending::Picture => {
+                Achievement::GoodEnding
+            }
+            Ending::Stab => Achievement::StabEnding,
+            Ending::Dino => Achievement::DinoEnding,
+        }
+    }
+
+    /// The title in English.
+    pub fn title(self) -> &'static str {
+        match self {
+            Achievement::GoodEnding => "Thanks Wife",
+            Achievement::StabEnding => "Regret",
+            Achievement::DinoEnding => "Dog Food",
+            Achievement::AllEndings => "Seen It All",
+            Achievement::SparedWife => "Maybe Later",
+            Achievement::QuickHunt => "Quick Hunt",
+            Achievement::FasterThanDog => "Faster Than Dog",
+            Achievement::FirstDuck => "Quack?",
+            Achievement::AllDucks => "Duck Hunter",
+        }
+    }
+
+    /// How to unlock it, in English.
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::GoodEnding => "Give wife something nice.",
+            Achievement::StabEnding => "Stab wife.",
+            Achievement::DinoEnding => "Feed yourself to the dog.",
+            Achievement::AllEndings => "Reach every ending.",
+            Achievement::SparedWife => "Decide not to stab wife.",
+            Achievement::QuickHunt => "Give wife something nice in under 5 minutes.",
+            Achievement::FasterThanDog => "Give wife something nice in under 2 minutes.",
+            Achievement::FirstDuck => "Find a rubber duck.",
+            Achievement::AllDucks => "Find every rubber duck.",
+        }
+    }
+
+    /// Hidden achievements don't say how to unlock them until they are.
+    pub fn hidden(self) -> bool {
+        matches!(
+            self,
+            Achievement::SparedWife | Achievement::FirstDuck | Achievement::AllDucks
+        )
+    }
+}
+
+/// Every achievement unlocked in any run, in the order they were unlocked.
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct UnlockedAchievements {
+    pub achievements: Vec<Achievement>,
+}
+
+impl Persistent for UnlockedAchievements {
+    const KEY: &'static str = "achievements";
+}
+
+impl UnlockedAchievements {
+    pub fn contains(&self, achievement: Achievement) -> bool {
+        self.achievements.contains(&achievement)
+    }
+}
+
+/// Event to unlock an [`Achievement`]. Nothing happens if it already is.
+#[derive(Event, Debug)]
+pub struct UnlockAchievement(pub Achievement);
+
+fn unlock_achievement(
+    trigger: Trigger<UnlockAchievement>,
+    mut commands: Commands,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    localization: Res<Localization>,
+    level_assets: Res<LevelAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut duck_layout: Local<Option<Handle<TextureAtlasLayout>>>,
+) {
+    let achievement = trigger.event().0;
+    if unlocked.contains(achievement) {
+        return;
+    }
+    unlocked.achievements.push(achievement);
+
+    let mut toast = Toast::new(format!(
+        "{}: {}",
+        localization.get("Achievement unlocked"),
+        localization.get(achievement.title())
+    ));
+    // Only the duck achievements have an icon.
+    if matches!(achievement, Achievement::FirstDuck | Achievement::AllDucks) {
+        let layout = duck_layout
+            .get_or_insert_with(|| texture_atlas_layouts.add(ducky_atlas_layout()))
+            .clone();
+        toast = toast
+            .with_icon(level_assets.ducky.clone())
+            .with_atlas(TextureAtlas { layout, index: 0 });
+    }
+    commands.trigger(toast);
+}
+
+fn unlock_ending_achievements(
+    trigger: Trigger<ReachEnding>,
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    unlocked_endings: Res<UnlockedEndings>,
+) {
+    let ending = trigger.event().0;
+    commands.trigger(UnlockAchievement(Achievement::for_ending(ending)));
+    // The ending may not be counted as unlocked yet.
+    let all_endings = Ending::ALL
+        .into_iter()
+        .all(|other| other == ending || unlocked_endings.endings.contains(&other));
+    if all_endings {
+        commands.trigger(UnlockAchievement(Achievement::AllEndings));
+    }
+    if ending.is_good() {
+        for (achievement, time) in SPEEDRUN_ACHIEVEMENTS {
+            if stats.time < time {
+                commands.trigger(UnlockAchievement(achievement));
+            }
+        }
+    }
+}
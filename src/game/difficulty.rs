@@ -0,0 +1,66 @@
+//! The difficulty picked before a run.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Difficulty>();
+    app.init_resource::<Difficulty>();
+}
+
+#[derive(
+    Resource,
+    Component,
+    Reflect,
+    Debug,
+    Display,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Resource)]
+pub enum Difficulty {
+    Story,
+    #[default]
+    Medium,
+    Brutal,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Story, Difficulty::Medium, Difficulty::Brutal];
+
+    /// Factor the respect score is multiplied with.
+    pub fn score_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Story => 0.5,
+            Difficulty::Medium => 1.0,
+            Difficulty::Brutal => 2.0,
+        }
+    }
+
+    /// How long the caveman has to stand around before he thinks of a hint
+    /// on his own, if he ever does.
+    pub fn hint_idle_time(self) -> Option<Duration> {
+        match self {
+            Difficulty::Story => Some(Duration::from_secs(20)),
+            Difficulty::Medium => Some(Duration::from_secs(60)),
+            Difficulty::Brutal => None,
+        }
+    }
+
+    /// How many increasingly explicit hints there are for every step.
+    pub fn max_hints(self) -> usize {
+        match self {
+            Difficulty::Story => 3,
+            Difficulty::Medium => 2,
+            Difficulty::Brutal => 1,
+        }
+    }
+}
@@ -1,54 +1,25 @@
 use std::time::Duration;
 
-use bevy::{
-    prelude::*,
-    render::texture::{ImageLoaderSettings, ImageSampler},
-};
+use bevy::prelude::*;
 use bevy_tweening::{lens::TransformPositionLens, Animator, EaseFunction, Tween, TweenCompleted};
-use bevy_yarnspinner::prelude::DialogueRunner;
-
-use crate::{asset_tracking::LoadResource, audio::SoundEffect, screens::Area};
 
-use super::{
-    inventory::{Inventory, Item},
-    level::LevelAssets,
-    movement::ActionsFrozen,
-    player::Player,
+use crate::{
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    audio::SoundEffect,
+    screens::Area,
 };
 
+use super::level::LevelAssets;
+
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<DinoLeg>();
-    app.load_resource::<DinoAssets>();
+    app.load_collection::<DinoAssets>();
 
-    app.add_systems(
-        Update,
-        (start_event, dino_stomp).run_if(in_state(Area::Outside)),
-    );
+    app.add_systems(Update, dino_stomp.run_if(in_state(Area::Outside)));
     app.observe(spawn_dino);
 }
 
-fn start_event(
-    mut dialogue_runner: Query<&mut DialogueRunner>,
-    mut actions_frozen: ResMut<ActionsFrozen>,
-    inventory: Res<Inventory>,
-    player: Query<&Transform, With<Player>>,
-) {
-    if !inventory.items.contains(&Item::WovenPapyrus) {
-        // inventory.items.push(Item::WovenPapyrus);
-        return;
-    }
-    for transform in &player {
-        if transform.translation.x < -440.0 {
-            return;
-        }
-    }
-    let mut dialogue_runner = dialogue_runner
-        .get_single_mut()
-        .expect("only one dialogue runner");
-    dialogue_runner.start_node("Dino");
-    actions_frozen.freeze();
-}
-
 #[derive(Event, Debug)]
 pub struct SpawnDino;
 
@@ -107,30 +78,19 @@ fn dino_stomp(
 #[derive(Component, Debug, Reflect)]
 struct DinoLeg;
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct DinoAssets {
-    #[dependency]
     pub dino_leg: Handle<Image>,
-    #[dependency]
     pub stomp: Handle<AudioSource>,
 }
 
-impl DinoAssets {
-    pub const PATH_DINO_LEG: &'static str = "images/dino_leg.png";
-    pub const PATH_STOMP: &'static str = "audio/sound_effects/step1.ogg";
-}
+impl FromCollection for DinoAssets {
+    const COLLECTION: &'static str = "dino";
 
-impl FromWorld for DinoAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            dino_leg: assets.load_with_settings(
-                DinoAssets::PATH_DINO_LEG,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            stomp: assets.load(DinoAssets::PATH_STOMP),
-        }
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            dino_leg: collection.image("dino_leg")?,
+            stomp: collection.audio("stomp")?,
+        })
     }
 }
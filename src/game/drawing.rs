@@ -0,0 +1,226 @@
+//! Freehand drawing with the mouse or touch, rasterized into an [`Image`] on
+//! the CPU so that it can be kept around and shown again later.
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    window::PrimaryWindow,
+};
+
+use crate::{screens::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            record_strokes,
+            undo_stroke.run_if(input_just_pressed(KeyCode::KeyZ)),
+            clear_canvas.run_if(input_just_pressed(KeyCode::KeyC)),
+            change_brush_size,
+        )
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// A sprite that can be drawn on while it is `active`.
+#[derive(Component, Debug)]
+pub struct DrawingCanvas {
+    pub image: Handle<Image>,
+    size: UVec2,
+    strokes: Vec<Stroke>,
+    /// Whether a stroke is currently being drawn.
+    drawing: bool,
+    /// Brush radius in canvas pixels.
+    pub brush_size: u32,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Stroke {
+    points: Vec<Vec2>,
+    brush_size: u32,
+}
+
+const INK: [u8; 4] = [0x2b, 0x1d, 0x14, 0xff];
+const MAX_BRUSH_SIZE: u32 = 5;
+
+impl DrawingCanvas {
+    /// Create a transparent canvas with one image pixel per sprite pixel.
+    pub fn new(images: &mut Assets<Image>, size: UVec2) -> Self {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        // Keep the drawing as blocky as the rest of the pixel art.
+        image.sampler = ImageSampler::nearest();
+        Self {
+            image: images.add(image),
+            size,
+            strokes: Vec::new(),
+            drawing: false,
+            brush_size: 1,
+            active: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strokes.is_empty()
+    }
+
+    /// Draw all strokes onto a blank image again.
+    fn redraw(&self, images: &mut Assets<Image>) {
+        let Some(image) = images.get_mut(&self.image) else {
+            return;
+        };
+        image.data.fill(0);
+        for stroke in &self.strokes {
+            for segment in stroke.points.windows(2) {
+                paint_line(image, self.size, segment[0], segment[1], stroke.brush_size);
+            }
+            if let [point] = stroke.points.as_slice() {
+                paint_line(image, self.size, *point, *point, stroke.brush_size);
+            }
+        }
+    }
+}
+
+/// Stamp the brush along the line from `start` to `end`.
+fn paint_line(image: &mut Image, size: UVec2, start: Vec2, end: Vec2, brush_size: u32) {
+    let steps = (start.distance(end) * 2.0).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+        let center = start.lerp(end, step as f32 / steps as f32);
+        paint_dot(image, size, center, brush_size);
+    }
+}
+
+fn paint_dot(image: &mut Image, size: UVec2, center: Vec2, brush_size: u32) {
+    let radius = brush_size as f32 - 0.5;
+    let min = (center - radius).floor().max(Vec2::ZERO);
+    let max = (center + radius).ceil().min(size.as_vec2() - 1.0);
+    for y in min.y as u32..=max.y as u32 {
+        for x in min.x as u32..=max.x as u32 {
+            let pixel = Vec2::new(x as f32, y as f32);
+            if pixel.distance(center) > radius {
+                continue;
+            }
+            let index = ((y * size.x + x) * 4) as usize;
+            image.data[index..index + 4].copy_from_slice(&INK);
+        }
+    }
+}
+
+/// Convert a position in the window to a pixel on the canvas sprite.
+fn canvas_pixel(
+    position: Vec2,
+    camera: (&Camera, &GlobalTransform),
+    canvas: &DrawingCanvas,
+    canvas_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let world = camera.0.viewport_to_world_2d(camera.1, position)?;
+    let local = canvas_transform
+        .affine()
+        .inverse()
+        .transform_point3(world.extend(0.0))
+        .xy();
+    let size = canvas.size.as_vec2();
+    let pixel = Vec2::new(local.x + size.x / 2.0, size.y / 2.0 - local.y);
+    (pixel.cmpge(Vec2::ZERO).all() && pixel.cmplt(size).all()).then_some(pixel)
+}
+
+fn record_strokes(
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut canvases: Query<(&mut DrawingCanvas, &GlobalTransform)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let touch = touches.iter().next();
+    let position = if mouse.pressed(MouseButton::Left) {
+        window.get_single().ok().and_then(Window::cursor_position)
+    } else {
+        touch.map(|touch| touch.position())
+    };
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    for (mut canvas, transform) in &mut canvases {
+        let pixel = position
+            .filter(|_| canvas.active)
+            .and_then(|position| canvas_pixel(position, camera, &canvas, transform));
+        let Some(pixel) = pixel else {
+            canvas.drawing = false;
+            continue;
+        };
+
+        let brush_size = canvas.brush_size;
+        let previous = if canvas.drawing {
+            canvas.strokes.last().and_then(|s| s.points.last()).copied()
+        } else {
+            canvas.strokes.push(Stroke {
+                points: Vec::new(),
+                brush_size,
+            });
+            canvas.drawing = true;
+            None
+        };
+        if previous == Some(pixel) {
+            continue;
+        }
+        canvas.strokes.last_mut().unwrap().points.push(pixel);
+
+        let size = canvas.size;
+        if let Some(image) = images.get_mut(&canvas.image) {
+            paint_line(image, size, previous.unwrap_or(pixel), pixel, brush_size);
+        }
+    }
+}
+
+fn undo_stroke(mut canvases: Query<&mut DrawingCanvas>, mut images: ResMut<Assets<Image>>) {
+    for mut canvas in &mut canvases {
+        if !canvas.active || canvas.drawing {
+            continue;
+        }
+        canvas.strokes.pop();
+        canvas.redraw(&mut images);
+    }
+}
+
+fn clear_canvas(mut canvases: Query<&mut DrawingCanvas>, mut images: ResMut<Assets<Image>>) {
+    for mut canvas in &mut canvases {
+        if !canvas.active {
+            continue;
+        }
+        canvas.strokes.clear();
+        canvas.drawing = false;
+        canvas.redraw(&mut images);
+    }
+}
+
+fn change_brush_size(input: Res<ButtonInput<KeyCode>>, mut canvases: Query<&mut DrawingCanvas>) {
+    for mut canvas in &mut canvases {
+        if !canvas.active {
+            continue;
+        }
+        if input.just_pressed(KeyCode::BracketLeft) {
+            canvas.brush_size = canvas.brush_size.saturating_sub(1).max(1);
+        }
+        if input.just_pressed(KeyCode::BracketRight) {
+            canvas.brush_size = (canvas.brush_size + 1).min(MAX_BRUSH_SIZE);
+        }
+    }
+}
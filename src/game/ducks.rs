@@ -0,0 +1,139 @@
+//! Rubber ducks hidden in the areas. They are picked up with E like items,
+//! but stay found across runs and unlock achievements instead.
+
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+    render::primitives::Aabb,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    achievements::{Achievement, UnlockAchievement},
+    level::{AreaLayouts, LevelAssets},
+    movement::ActionsFrozen,
+    player::{Player, PlayerAssets},
+};
+use crate::{
+    audio::SoundEffect,
+    persistence::{PersistResource, Persistent},
+    screens::{Area, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.persist_resource::<FoundDucks>();
+
+    app.add_systems(OnEnter(Area::Cave), spawn_ducks);
+    app.add_systems(OnEnter(Area::Outside), spawn_ducks);
+    app.add_systems(
+        Update,
+        find_duck
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The ids of every duck that was found in any run.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct FoundDucks {
+    pub ids: Vec<String>,
+}
+
+impl Persistent for FoundDucks {
+    const KEY: &'static str = "found_ducks";
+}
+
+/// How many ducks are hidden in all areas.
+pub fn duck_count(layouts: &AreaLayouts) -> usize {
+    layouts.iter().map(|(_, layout)| layout.ducks.len()).sum()
+}
+
+#[derive(Component, Debug)]
+struct Duck {
+    id: String,
+}
+
+/// The layout of `ducky.png`, which has a standing duck in its first cell.
+pub(super) fn ducky_atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None)
+}
+
+fn spawn_ducks(
+    mut commands: Commands,
+    area: Res<State<Area>>,
+    found: Res<FoundDucks>,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<AreaLayouts>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let layout = texture_atlas_layouts.add(ducky_atlas_layout());
+    for placement in &layouts.of(*area.get()).ducks {
+        if found.ids.contains(&placement.id) {
+            continue;
+        }
+        commands.spawn((
+            Name::new(format!("Duck {}", placement.id)),
+            Duck {
+                id: placement.id.clone(),
+            },
+            SpriteBundle {
+                texture: level_assets.ducky.clone(),
+                transform: Transform::from_translation(placement.translation)
+                    .with_scale(Vec3::splat(1.5)),
+                ..default()
+            },
+            TextureAtlas {
+                layout: layout.clone(),
+                index: 0,
+            },
+            StateScoped(*area.get()),
+        ));
+    }
+}
+
+fn find_duck(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    player: Query<(&Aabb, &Transform), With<Player>>,
+    ducks: Query<(Entity, &Aabb, &Transform, &Duck)>,
+    mut found: ResMut<FoundDucks>,
+    layouts: Res<AreaLayouts>,
+    player_assets: Res<PlayerAssets>,
+    actions_frozen: Res<ActionsFrozen>,
+) {
+    if actions_frozen.is_frozen() || !input.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let Ok((player_aabb, player_transform)) = player.get_single() else {
+        return;
+    };
+    let player_aabb2d = Aabb2d::new(
+        player_transform.translation.xy(),
+        player_aabb.half_extents.xy() * player_transform.scale.xy(),
+    );
+    for (entity, duck_aabb, duck_transform, duck) in &ducks {
+        let duck_aabb2d = Aabb2d::new(
+            duck_transform.translation.xy(),
+            duck_aabb.half_extents.xy() * duck_transform.scale.xy(),
+        );
+        if !player_aabb2d.intersects(&duck_aabb2d) {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        found.ids.push(duck.id.clone());
+        commands.spawn((
+            AudioBundle {
+                source: player_assets.item_pickup.clone(),
+                settings: PlaybackSettings::DESPAWN,
+            },
+            SoundEffect,
+            Name::from("Duck sound"),
+        ));
+
+        commands.trigger(UnlockAchievement(Achievement::FirstDuck));
+        if found.ids.len() >= duck_count(&layouts) {
+            commands.trigger(UnlockAchievement(Achievement::AllDucks));
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! The different ways the game can end and which of them were reached so far.
+
+use bevy::prelude::*;
+use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    persistence::{PersistResource, Persistent},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ReachedEnding>();
+    app.register_type::<UnlockedEndings>();
+    app.init_resource::<ReachedEnding>();
+    app.persist_resource::<UnlockedEndings>();
+    app.load_collection::<EndingAssets>();
+    app.observe(reach_ending);
+
+    app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
+        commands.insert_resource(ReachedEnding::default())
+    });
+}
+
+#[derive(Reflect, Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ending {
+    /// The wife got a written paper she didn't recognize.
+    Good,
+    /// The wife got a love letter.
+    Love,
+    /// The wife got a paper with her name on it.
+    Portrait,
+    /// The wife got a paper with the player's name on it.
+    Name,
+    /// The wife got a drawing without any text.
+    Picture,
+    /// The caveman stabbed his wife.
+    Stab,
+    /// The caveman went after the dino.
+    Dino,
+}
+
+/// Everything the end screen shows for an [`Ending`].
+pub struct EndingInfo {
+    pub title: &'static str,
+    pub text: &'static str,
+}
+
+impl Ending {
+    pub const ALL: [Ending; 7] = [
+        Ending::Good,
+        Ending::Love,
+        Ending::Portrait,
+        Ending::Name,
+        Ending::Picture,
+        Ending::Stab,
+        Ending::Dino,
+    ];
+
+    /// The id used by the `end_game` Yarn command, and for the music and art
+    /// of the ending in the `endings` collection of the asset manifest.
+    pub fn id(self) -> &'static str {
+        match self {
+            Ending::Good => "good",
+            Ending::Love => "love",
+            Ending::Portrait => "portrait",
+            Ending::Name => "name",
+            Ending::Picture => "picture",
+            Ending::Stab => "stab",
+            Ending::Dino => "dino",
+        }
+    }
+
+    /// Parse the id used by the `end_game` Yarn command.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Ending::ALL.into_iter().find(|ending| ending.id() == id)
+    }
+
+    pub fn info(self) -> EndingInfo {
+        match self {
+            Ending::Good => EndingInfo {
+                title: "Mission passed!",
+                text: "Respect+",
+            },
+            Ending::Love => EndingInfo {
+                title: "Love letter.",
+                text: "Love+",
+            },
+            Ending::Portrait => EndingInfo {
+                title: "Wife on paper!",
+                text: "Vanity+",
+            },
+            Ending::Name => EndingInfo {
+                title: "Autograph.",
+                text: "Fame+",
+            },
+            Ending::Picture => EndingInfo {
+                title: "Cave painting.",
+                text: "Art+",
+            },
+            Ending::Stab => EndingInfo {
+                title: "Wife gone.",
+                text: "Regret+",
+            },
+            Ending::Dino => EndingInfo {
+                title: "Dog hungry.",
+                text: "Caveman-",
+            },
+        }
+    }
+
+    /// Whether the wife got her paper, in any of its variants.
+    pub fn is_good(self) -> bool {
+        matches!(
+            self,
+            Ending::Good | Ending::Love | Ending::Portrait | Ending::Name | Ending::Picture
+        )
+    }
+
+    fn index(self) -> usize {
+        Ending::ALL
+            .iter()
+            .position(|ending| *ending == self)
+            .unwrap()
+    }
+}
+
+/// The ending of the current run.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ReachedEnding(pub Option<Ending>);
+
+/// Every ending that was reached in any run.
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct UnlockedEndings {
+    pub endings: Vec<Ending>,
+}
+
+impl Persistent for UnlockedEndings {
+    const KEY: &'static str = "unlocked_endings";
+    const SAVED_TOAST: Option<&'static str> = Some("Progress saved");
+}
+
+/// Music and art of every [`Ending`], in the order of [`Ending::ALL`].
+#[derive(Resource, Reflect, Clone)]
+pub struct EndingAssets {
+    music: Vec<Handle<AudioSource>>,
+    art: Vec<Handle<Image>>,
+}
+
+impl EndingAssets {
+    pub fn music(&self, ending: Ending) -> Handle<AudioSource> {
+        self.music[ending.index()].clone()
+    }
+
+    pub fn art(&self, ending: Ending) -> Handle<Image> {
+        self.art[ending.index()].clone()
+    }
+}
+
+impl FromCollection for EndingAssets {
+    const COLLECTION: &'static str = "endings";
+
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            music: Ending::ALL
+                .iter()
+                .map(|ending| collection.audio(ending.id()))
+                .collect::<Result<_, _>>()?,
+            art: Ending::ALL
+                .iter()
+                .map(|ending| collection.image(ending.id()))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Event to end the game with the given [`Ending`].
+#[derive(Event, Debug)]
+pub struct ReachEnding(pub Ending);
+
+fn reach_ending(
+    trigger: Trigger<ReachEnding>,
+    mut reached: ResMut<ReachedEnding>,
+    mut unlocked: ResMut<UnlockedEndings>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let ending = trigger.event().0;
+    reached.0 = Some(ending);
+    if !unlocked.endings.contains(&ending) {
+        unlocked.endings.push(ending);
+    }
+    next_screen.set(Screen::End);
+}
@@ -7,28 +7,30 @@ use std::time::Duration;
 use bevy::{
     math::bounding::{Aabb2d, IntersectsVolume},
     prelude::*,
-    render::{
-        primitives::Aabb,
-        texture::{ImageLoaderSettings, ImageSampler},
-    },
+    render::primitives::Aabb,
 };
 use bevy_yarnspinner::prelude::DialogueRunner;
 
 use crate::{
-    asset_tracking::LoadResource, audio::SoundEffect, game::animation::Animation, screens::Area,
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    audio::SoundEffect,
+    game::animation::Animation,
+    screens::Area,
 };
 
 use super::{
     animation::{AnimationData, AnimationState},
-    inventory::{Inventory, Item},
+    inventory::Inventory,
     level::Level,
-    movement::ActionsFrozen,
+    movement::{ActionsFrozen, FreezeReason},
     player::{Player, PlayerAssets},
+    recipe::{Recipe, Station, FIRE_NODE},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Fire>();
-    app.load_resource::<FireAssets>();
+    app.load_collection::<FireAssets>();
     app.add_systems(OnEnter(Area::Cave), spawn_fire);
     app.add_systems(Update, place_banana.run_if(in_state(Area::Cave)));
 }
@@ -76,34 +78,24 @@ fn spawn_fire(
     ));
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct FireAssets {
-    #[dependency]
     pub fire: Handle<Image>,
 }
 
-impl FireAssets {
-    pub const PATH_FIRE: &'static str = "images/campfire.png";
-}
+impl FromCollection for FireAssets {
+    const COLLECTION: &'static str = "fire";
 
-impl FromWorld for FireAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            fire: assets.load_with_settings(
-                FireAssets::PATH_FIRE,
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve the pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-        }
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            fire: collection.image("fire")?,
+        })
     }
 }
 
 fn place_banana(
     mut commands: Commands,
-    mut dialogue_runner: Query<&mut DialogueRunner>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
     mut inventory: ResMut<Inventory>,
     mut level: ResMut<Level>,
     input: Res<ButtonInput<KeyCode>>,
@@ -129,24 +121,27 @@ fn place_banana(
                 fire_aabb.half_extents.xy() * fire_transform.scale.xy(),
             );
             if player_aabb2d.intersects(&fire_aabb2d) {
-                let mut dialogue_runner = dialogue_runner
+                let (runner, mut dialogue_runner) = dialogue_runner
                     .get_single_mut()
                     .expect("only one dialogue runner");
 
-                let item = Item::Banana;
-                let Some(index) = inventory.items.iter().position(|x| *x == item) else {
-                    if !level.items.contains(&Item::BurntBanana) {
-                        dialogue_runner.start_node("Fire");
-                        actions_frozen.freeze();
+                let Some(recipe) = Recipe::at(Station::Fire).find(|recipe| {
+                    inventory.contains(recipe.input)
+                        && recipe.is_unlocked_in(dialogue_runner.variable_storage())
+                }) else {
+                    let burnt = Recipe::at(Station::Fire)
+                        .any(|recipe| level.items.iter().any(|item| item.id() == recipe.output));
+                    if !burnt {
+                        dialogue_runner.start_node(FIRE_NODE);
+                        actions_frozen.freeze(FreezeReason::Dialogue, runner);
                     }
                     return;
                 };
-                inventory.items.remove(index);
-                level.items.push(Item::BurntBanana);
-
-                let vars = dialogue_runner.variable_storage_mut();
-
-                vars.set(format!("$_has_{}", item), false.into()).unwrap();
+                recipe.make(
+                    &mut inventory,
+                    &mut level,
+                    dialogue_runner.variable_storage_mut(),
+                );
 
                 commands.spawn((
                     AudioBundle {
@@ -156,8 +151,10 @@ fn place_banana(
                     SoundEffect,
                     Name::from("Drop sound"),
                 ));
-                dialogue_runner.start_node("DroppedBanana");
-                actions_frozen.freeze();
+                if let Some(node) = recipe.node {
+                    dialogue_runner.start_node(node);
+                    actions_frozen.freeze(FreezeReason::Dialogue, runner);
+                }
             }
         }
     }
@@ -0,0 +1,198 @@
+//! A translucent ghost caveman that follows the fastest run to the good
+//! ending, so players can race against themselves. The ghost is taken from
+//! the replay recorded for that run.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::{PersistResource, Persistent},
+    replay::{Replay, RunRecorded},
+    screens::{Area, Screen},
+    AppSet,
+};
+
+use super::{
+    animation::{Animation, AnimationState},
+    ending::Ending,
+    player::{caveman_animation, caveman_atlas_layout, PlayerAssets},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GhostSettings>();
+    app.persist_resource::<GhostSettings>();
+    app.persist_resource::<BestGhost>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_ghost.run_if(ghost_enabled));
+    app.add_systems(
+        Update,
+        play_ghost
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(keep_best_ghost);
+}
+
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct GhostSettings {
+    /// Whether the ghost of the best run is shown.
+    pub enabled: bool,
+}
+
+impl Persistent for GhostSettings {
+    const KEY: &'static str = "ghost_settings";
+}
+
+/// Where the player was at one point of a run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct GhostSample {
+    area: Area,
+    position: Vec2,
+    walking: bool,
+    flip_x: bool,
+}
+
+/// The fastest run that reached the good ending, sampled every
+/// [`GHOST_SAMPLE_INTERVAL`].
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+struct BestGhost {
+    samples: Vec<GhostSample>,
+}
+
+impl Persistent for BestGhost {
+    const KEY: &'static str = "best_ghost";
+}
+
+#[derive(Component, Debug)]
+struct Ghost {
+    elapsed: Duration,
+}
+
+const GHOST_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+const GHOST_ALPHA: f32 = 0.35;
+
+fn ghost_enabled(settings: Res<GhostSettings>, best: Res<BestGhost>) -> bool {
+    settings.enabled && !best.samples.is_empty()
+}
+
+fn spawn_ghost(
+    mut commands: Commands,
+    player_assets: Res<PlayerAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let animation = caveman_animation();
+    commands.spawn((
+        Name::new("Ghost"),
+        Ghost {
+            elapsed: Duration::ZERO,
+        },
+        SpriteBundle {
+            texture: player_assets.caveman.clone(),
+            sprite: Sprite {
+                color: Color::WHITE.with_alpha(GHOST_ALPHA),
+                ..default()
+            },
+            // Behind the player.
+            transform: Transform::from_scale(Vec2::splat(8.0).extend(1.0))
+                .with_translation(Vec3::new(0.0, 0.0, -1.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        TextureAtlas {
+            layout: texture_atlas_layouts.add(caveman_atlas_layout()),
+            index: animation.get_atlas_index(),
+        },
+        animation,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn keep_best_ghost(trigger: Trigger<RunRecorded>, mut best: ResMut<BestGhost>) {
+    let replay = &trigger.event().0;
+    if !replay.outcome.ending.is_some_and(Ending::is_good) {
+        return;
+    }
+    let samples = ghost_samples(replay);
+    if best.samples.is_empty() || samples.len() < best.samples.len() {
+        best.samples = samples;
+    }
+}
+
+/// Sample where the player was in the replay every [`GHOST_SAMPLE_INTERVAL`].
+fn ghost_samples(replay: &Replay) -> Vec<GhostSample> {
+    let mut samples: Vec<GhostSample> = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    let mut player = None;
+    for frame in &replay.frames {
+        elapsed += frame.delta;
+        player = frame.player.or(player);
+        let Some(player) = player else {
+            continue;
+        };
+        while GHOST_SAMPLE_INTERVAL * samples.len() as u32 <= elapsed {
+            samples.push(GhostSample {
+                area: player.area,
+                position: player.position,
+                walking: false,
+                flip_x: player.flip_x,
+            });
+        }
+    }
+    // The player walks whenever they move on to the next sample.
+    for index in 1..samples.len() {
+        let next = samples[index];
+        let sample = &mut samples[index - 1];
+        sample.walking = sample.area == next.area && sample.position != next.position;
+    }
+    samples
+}
+
+fn play_ghost(
+    time: Res<Time>,
+    area: Res<State<Area>>,
+    best: Res<BestGhost>,
+    mut ghosts: Query<(
+        &mut Ghost,
+        &mut Transform,
+        &mut Sprite,
+        &mut Animation,
+        &mut Visibility,
+    )>,
+) {
+    for (mut ghost, mut transform, mut sprite, mut animation, mut visibility) in &mut ghosts {
+        ghost.elapsed += time.delta();
+        let index = (ghost.elapsed.as_secs_f32() / GHOST_SAMPLE_INTERVAL.as_secs_f32()) as usize;
+        // The ghost vanishes once its run is over.
+        let Some(sample) = best.samples.get(index) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        if sample.area != *area.get() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+
+        // Move smoothly between samples, but not across area changes.
+        let mut position = sample.position;
+        if let Some(next) = best
+            .samples
+            .get(index + 1)
+            .filter(|next| next.area == sample.area)
+        {
+            let t = ghost.elapsed.as_secs_f32() / GHOST_SAMPLE_INTERVAL.as_secs_f32() % 1.0;
+            position = position.lerp(next.position, t);
+        }
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        sprite.flip_x = sample.flip_x;
+        animation.update_state(if sample.walking {
+            AnimationState::Walking
+        } else {
+            AnimationState::Idling
+        });
+    }
+}
@@ -0,0 +1,300 @@
+//! Hints for players that are stuck. The caveman thinks out loud about the
+//! next step of the puzzle after standing around for a while, or when T is
+//! pressed. Every further hint for the same step is more explicit, and the
+//! wife gives the last one in her own words if she is around.
+//!
+//! The step is the first one of the chain that isn't done yet and that the
+//! [`PuzzleGraph`] says the caveman has everything for.
+//!
+//! How long it takes and how explicit hints get depends on the [`Difficulty`].
+
+use std::{collections::BTreeSet, time::Duration};
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_yarnspinner::prelude::DialogueRunner;
+
+use super::{
+    difficulty::Difficulty,
+    ending::Ending,
+    inventory::{Inventory, Item},
+    level::{AreaLayouts, Level},
+    movement::ActionsFrozen,
+    speech::{find_speaker, Say},
+};
+use crate::{
+    puzzle::{atoms, dropped_atom, ending_atom, held_atom, PuzzleGraph},
+    screens::Screen,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HintState>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_hints);
+    app.add_systems(
+        Update,
+        (
+            tick_idle_time.in_set(AppSet::TickTimers),
+            (
+                update_hint_step,
+                give_idle_hint,
+                request_hint.run_if(input_just_pressed(KeyCode::KeyT)),
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(give_hint);
+}
+
+/// The step of the puzzle chain the caveman is at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintStep {
+    AskWife,
+    FindPlant,
+    GetKnife,
+    CutPlant,
+    MakeCloth,
+    GoOutside,
+    PickUpPaper,
+    BurnBanana,
+    PickUpBurntBanana,
+    ShowBurntBanana,
+    WriteNote,
+    GiveNote,
+}
+
+impl HintStep {
+    pub const ALL: [HintStep; 12] = [
+        HintStep::AskWife,
+        HintStep::FindPlant,
+        HintStep::GetKnife,
+        HintStep::CutPlant,
+        HintStep::MakeCloth,
+        HintStep::GoOutside,
+        HintStep::PickUpPaper,
+        HintStep::BurnBanana,
+        HintStep::PickUpBurntBanana,
+        HintStep::ShowBurntBanana,
+        HintStep::WriteNote,
+        HintStep::GiveNote,
+    ];
+
+    /// What the step gets, as named in the [`PuzzleGraph`].
+    pub fn goal(self) -> String {
+        match self {
+            HintStep::AskWife => "$asked_for_plant".to_string(),
+            HintStep::FindPlant => held_atom(Item::PAPYRUS),
+            HintStep::GetKnife => held_atom(Item::KNIFE),
+            HintStep::CutPlant => held_atom(Item::PAPYRUS_STRIPS),
+            HintStep::MakeCloth => held_atom(Item::WOVEN_PAPYRUS),
+            HintStep::GoOutside => dropped_atom(Item::PAPER),
+            HintStep::PickUpPaper => held_atom(Item::PAPER),
+            HintStep::BurnBanana => dropped_atom(Item::BURNT_BANANA),
+            HintStep::PickUpBurntBanana => held_atom(Item::BURNT_BANANA),
+            HintStep::ShowBurntBanana => "$learned_pen".to_string(),
+            HintStep::WriteNote => held_atom(Item::WRITTEN_PAPER),
+            HintStep::GiveNote => ending_atom(Ending::Good),
+        }
+    }
+
+    /// The step to hint at when the caveman has `got` everything in it, as
+    /// named by [`atoms`].
+    pub fn current(graph: &PuzzleGraph, got: &BTreeSet<String>) -> Self {
+        let open = HintStep::ALL.into_iter().filter(|step| {
+            let goal = step.goal();
+            !got.contains(&goal) && !got.iter().any(|atom| graph.leads_to(&goal, atom))
+        });
+        open.clone()
+            .find(|step| graph.can_get(&step.goal(), got))
+            .or_else(|| open.clone().next())
+            .unwrap_or(HintStep::GiveNote)
+    }
+
+    /// The hints for this step in English, from vague to explicit.
+    pub fn hints(self) -> [&'static str; 3] {
+        match self {
+            HintStep::AskWife => [
+                "Me forget what hunt...",
+                "Wife know everything. Ask wife.",
+                "Go to wife in cave, press E.",
+            ],
+            HintStep::FindPlant => [
+                "Plant... where plant grow?",
+                "Plant grow outside, not in cave.",
+                "Papyrus outside. Go there, press E.",
+            ],
+            HintStep::GetKnife => [
+                "Plant too big. Need smaller.",
+                "Sharp thing cut plant. Where sharp thing?",
+                "Pick up knife, then use plant.",
+            ],
+            HintStep::CutPlant => [
+                "Knife sharp... plant big...",
+                "Knife cut plant!",
+                "Click plant in inventory.",
+            ],
+            HintStep::MakeCloth => [
+                "Strips no use for me.",
+                "Wife good with hands.",
+                "Bring strips to wife, press E.",
+            ],
+            HintStep::GoOutside => [
+                "Nice cloth. Show world!",
+                "Take cloth outside.",
+                "Walk outside with cloth.",
+            ],
+            HintStep::PickUpPaper => [
+                "Dog leave something?",
+                "Where cloth fall?",
+                "Pick up paper outside, press E.",
+            ],
+            HintStep::BurnBanana => [
+                "Paper empty. Need write... with what?",
+                "Burnt things make black.",
+                "Put banana in fire, press E at fire.",
+            ],
+            HintStep::PickUpBurntBanana => [
+                "Banana black now. Hmm.",
+                "Burnt banana still in fire.",
+                "Pick up burnt banana at fire.",
+            ],
+            HintStep::ShowBurntBanana => [
+                "Black banana... use?",
+                "Wife know everything. Ask about banana.",
+                "Bring burnt banana to wife, press E.",
+            ],
+            HintStep::WriteNote => [
+                "Me can write now!",
+                "Write something on paper.",
+                "Click paper in inventory to write.",
+            ],
+            HintStep::GiveNote => [
+                "Wife like this, maybe.",
+                "Paper for wife!",
+                "Bring written paper to wife, press E.",
+            ],
+        }
+    }
+
+    /// The most explicit hint for this step in English, in the words of
+    /// the wife.
+    pub fn wife_hint(self) -> &'static str {
+        match self {
+            HintStep::AskWife => "You forget again? Come talk to me, press E.",
+            HintStep::FindPlant => "Me need plant. Papyrus grow outside, press E there.",
+            HintStep::GetKnife => "Plant too big. Pick up knife, then use plant.",
+            HintStep::CutPlant => "You have knife. Click plant in inventory.",
+            HintStep::MakeCloth => "Give me strips, press E. Me make cloth.",
+            HintStep::GoOutside => "Nice cloth. Take it outside, show world.",
+            HintStep::PickUpPaper => "Cloth fall outside. Pick up paper, press E.",
+            HintStep::BurnBanana => "Banana in fire make black. Press E at fire.",
+            HintStep::PickUpBurntBanana => "Burnt banana still in fire. Pick up, press E.",
+            HintStep::ShowBurntBanana => "Bring me burnt banana, press E.",
+            HintStep::WriteNote => "Click paper in inventory. Write me something.",
+            HintStep::GiveNote => "That for me? Bring paper, press E.",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct HintState {
+    step: Option<HintStep>,
+    /// How many hints were given for the current step.
+    given: usize,
+    /// How long the caveman has been free to act since the last progress or
+    /// hint.
+    idle: Duration,
+}
+
+fn reset_hints(mut commands: Commands) {
+    commands.insert_resource(HintState::default());
+}
+
+fn tick_idle_time(
+    time: Res<Time>,
+    actions_frozen: Res<ActionsFrozen>,
+    mut state: ResMut<HintState>,
+) {
+    if !actions_frozen.is_frozen() {
+        state.idle += time.delta();
+    }
+}
+
+fn update_hint_step(
+    inventory: Res<Inventory>,
+    level: Res<Level>,
+    layouts: Res<AreaLayouts>,
+    graph: Option<Res<PuzzleGraph>>,
+    dialogue_runner: Query<&DialogueRunner>,
+    mut state: ResMut<HintState>,
+) {
+    // Without the searched puzzle there is no step to hint at yet.
+    let Some(graph) = graph else {
+        return;
+    };
+    let Ok(dialogue_runner) = dialogue_runner.get_single() else {
+        return;
+    };
+    let got = atoms(
+        &inventory.items,
+        &level.items,
+        &layouts.start_items(),
+        &dialogue_runner.variable_storage().variables(),
+    );
+    let step = HintStep::current(&graph, &got);
+    if state.step != Some(step) {
+        *state = HintState {
+            step: Some(step),
+            ..default()
+        };
+    }
+}
+
+fn give_idle_hint(mut commands: Commands, state: Res<HintState>, difficulty: Res<Difficulty>) {
+    let Some(idle_time) = difficulty.hint_idle_time() else {
+        return;
+    };
+    if state.idle >= idle_time && state.given < difficulty.max_hints() {
+        commands.trigger(GiveHint);
+    }
+}
+
+fn request_hint(mut commands: Commands, actions_frozen: Res<ActionsFrozen>) {
+    if !actions_frozen.is_frozen() {
+        commands.trigger(GiveHint);
+    }
+}
+
+/// Event to give the next hint for the current step. Once the most explicit
+/// hint the difficulty allows was given, it is repeated.
+#[derive(Event, Debug)]
+struct GiveHint;
+
+fn give_hint(
+    _trigger: Trigger<GiveHint>,
+    mut commands: Commands,
+    mut state: ResMut<HintState>,
+    difficulty: Res<Difficulty>,
+    names: Query<(Entity, &Name)>,
+) {
+    let Some(step) = state.step else {
+        return;
+    };
+    let max_hints = difficulty.max_hints();
+    if max_hints == 0 {
+        return;
+    }
+    let index = state.given.min(max_hints - 1);
+    let hints = step.hints();
+    let wife = find_speaker(&names, "Wife").filter(|_| index == hints.len() - 1);
+    let hint = match wife {
+        Some(wife) => Some((wife, step.wife_hint())),
+        None => find_speaker(&names, "Player").map(|player| (player, hints[index])),
+    };
+    if let Some((speaker, hint)) = hint {
+        commands.trigger_targets(Say::new(hint), speaker);
+    }
+    state.given = (state.given + 1).min(max_hints);
+    state.idle = Duration::ZERO;
+}
@@ -0,0 +1,351 @@
+//! A log of every dialogue line and chosen option, so that clues can be read
+//! again after the dialogue moved on. Press H to show it.
+//!
+//! The log is kept in the save data across runs, with the newest run last.
+
+use std::time::Duration;
+
+use bevy::{
+    input::{common_conditions::input_just_pressed, mouse::MouseWheel},
+    prelude::*,
+    ui::Val::*,
+};
+use bevy_yarnspinner::{
+    events::{DialogueCompleteEvent, PresentLineEvent, PresentOptionsEvent},
+    prelude::{DialogueOption, DialogueRunner, LocalizedLine, OptionId, YarnSpinnerSystemSet},
+};
+use bevy_yarnspinner_example_dialogue_view::ExampleYarnSpinnerDialogueViewSystemSet;
+use serde::{Deserialize, Serialize};
+
+use super::movement::{ActionsFrozen, FreezeReason};
+use crate::{
+    localization::Localization,
+    persistence::{PersistResource, Persistent},
+    screens::Screen,
+    theme::prelude::*,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.persist_resource::<DialogueHistory>();
+    app.init_resource::<OfferedOptions>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), start_run);
+    app.add_systems(
+        Update,
+        (
+            // Before the view handles the player's input, and after it
+            // passed the picked option on to the runner.
+            (record_lines, record_offered_options, pick_option)
+                .chain()
+                .after(YarnSpinnerSystemSet)
+                .before(ExampleYarnSpinnerDialogueViewSystemSet),
+            record_chosen_option.after(ExampleYarnSpinnerDialogueViewSystemSet),
+            (
+                toggle_history.run_if(input_just_pressed(KeyCode::KeyH)),
+                close_history.run_if(input_just_pressed(KeyCode::Escape)),
+                scroll_history,
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How many entries are kept, older ones are dropped.
+const MAX_ENTRIES: usize = 300;
+
+/// Every line shown by the dialogue runner, oldest first.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct DialogueHistory {
+    /// Number of runs that were started.
+    pub runs: u32,
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl Persistent for DialogueHistory {
+    const KEY: &'static str = "dialogue_history";
+    // Every line changes the history, which would rewrite all of it.
+    const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The run the line was shown in, starting at 1.
+    pub run: u32,
+    /// The character name of the line, if it has one. Lines without one are
+    /// said by the caveman.
+    pub speaker: Option<String>,
+    /// The line in the language it was shown in.
+    pub text: String,
+    /// Whether the player picked this line as an option.
+    pub chosen: bool,
+}
+
+impl DialogueHistory {
+    fn push(&mut self, line: &LocalizedLine, chosen: bool) {
+        self.entries.push(HistoryEntry {
+            run: self.runs,
+            speaker: line.character_name().map(ToString::to_string),
+            text: line.text_without_character_name(),
+            chosen,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+}
+
+/// The options the dialogue runner is waiting for the player to pick from,
+/// in the order the dialogue view lists them.
+#[derive(Resource, Debug, Default)]
+struct OfferedOptions {
+    options: Vec<DialogueOption>,
+    /// The option the player's input picks in this update.
+    picked: Option<OptionId>,
+}
+
+fn start_run(mut history: ResMut<DialogueHistory>) {
+    history.runs += 1;
+}
+
+fn record_lines(mut events: EventReader<PresentLineEvent>, mut history: ResMut<DialogueHistory>) {
+    for event in events.read() {
+        history.push(&event.line, false);
+    }
+}
+
+fn record_offered_options(
+    mut events: EventReader<PresentOptionsEvent>,
+    mut completed: EventReader<DialogueCompleteEvent>,
+    mut offered: ResMut<OfferedOptions>,
+) {
+    if completed.read().count() > 0 {
+        offered.options.clear();
+    }
+    for event in events.read() {
+        offered.options = event
+            .options
+            .iter()
+            .filter(|option| option.is_available)
+            .cloned()
+            .collect();
+    }
+}
+
+/// Find the option the dialogue view picks for the player's input: the one
+/// whose number key was pressed, or whose button was clicked. The view
+/// removes the buttons once it passed the option on, so this looks before.
+fn pick_option(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<(&Interaction, &Children), (With<Button>, Changed<Interaction>)>,
+    texts: Query<&Text>,
+    mut offered: ResMut<OfferedOptions>,
+) {
+    let by_key = OPTION_KEYS
+        .iter()
+        .zip(&offered.options)
+        .find(|(option_keys, _)| keys.any_just_pressed(option_keys.iter().copied()))
+        .map(|(_, option)| option.id);
+    let by_button = || {
+        buttons
+            .iter()
+            .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+            .flat_map(|(_, children)| texts.iter_many(children))
+            .find_map(|text| {
+                let label = &text.sections.last()?.value;
+                offered
+                    .options
+                    .iter()
+                    .find(|option| option.line.text == *label)
+                    .map(|option| option.id)
+            })
+    };
+    offered.picked = by_key.or_else(by_button);
+}
+
+/// Once the view passed the picked option to [`DialogueRunner::select_option`],
+/// the runner no longer waits for a selection and the option is recorded.
+fn record_chosen_option(
+    dialogue_runners: Query<&DialogueRunner>,
+    mut offered: ResMut<OfferedOptions>,
+    mut history: ResMut<DialogueHistory>,
+) {
+    let Some(picked) = offered.picked.take() else {
+        return;
+    };
+    if dialogue_runners
+        .iter()
+        .any(DialogueRunner::is_waiting_for_option_selection)
+    {
+        return;
+    }
+    if let Some(option) = offered.options.iter().find(|option| option.id == picked) {
+        history.push(&option.line, true);
+    }
+    offered.options.clear();
+}
+
+/// The keys the dialogue view picks each option with.
+const OPTION_KEYS: [[KeyCode; 2]; 9] = [
+    [KeyCode::Digit1, KeyCode::Numpad1],
+    [KeyCode::Digit2, KeyCode::Numpad2],
+    [KeyCode::Digit3, KeyCode::Numpad3],
+    [KeyCode::Digit4, KeyCode::Numpad4],
+    [KeyCode::Digit5, KeyCode::Numpad5],
+    [KeyCode::Digit6, KeyCode::Numpad6],
+    [KeyCode::Digit7, KeyCode::Numpad7],
+    [KeyCode::Digit8, KeyCode::Numpad8],
+    [KeyCode::Digit9, KeyCode::Numpad9],
+];
+
+#[derive(Component)]
+struct HistoryPanel;
+
+/// The list inside the panel, moved up and down to scroll.
+#[derive(Component, Default)]
+struct HistoryList {
+    /// Distance scrolled up from the newest entry.
+    offset: f32,
+}
+
+const PANEL_SIZE: Vec2 = Vec2::new(700.0, 500.0);
+const SCROLL_LINE_HEIGHT: f32 = 30.0;
+
+fn toggle_history(
+    mut commands: Commands,
+    panel: Query<Entity, With<HistoryPanel>>,
+    history: Res<DialogueHistory>,
+    localization: Res<Localization>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) {
+    if let Ok(entity) = panel.get_single() {
+        commands.entity(entity).despawn_recursive();
+        actions_frozen.unfreeze(FreezeReason::Pause, entity);
+        return;
+    }
+    // Dialogue is what the history is for, but the paper and cutscenes
+    // need the keyboard.
+    if actions_frozen.is_frozen_ignoring(&[FreezeReason::Dialogue]) {
+        return;
+    }
+    let panel = commands
+        .ui_root()
+        .insert((
+            Name::new("History Panel"),
+            HistoryPanel,
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            children.header(localization.get("History"));
+            children
+                .spawn((
+                    Name::new("History Viewport"),
+                    NodeBundle {
+                        style: Style {
+                            width: Px(PANEL_SIZE.x),
+                            height: Px(PANEL_SIZE.y),
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::FlexEnd,
+                            overflow: Overflow::clip_y(),
+                            padding: UiRect::all(Px(10.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(ui_palette::NODE_BACKGROUND),
+                        ..default()
+                    },
+                ))
+                .with_children(|children| {
+                    children
+                        .spawn((
+                            Name::new("History List"),
+                            HistoryList::default(),
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    flex_shrink: 0.0,
+                                    row_gap: Px(6.0),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ))
+                        .with_children(|children| {
+                            spawn_entries(children, &history, &localization);
+                        });
+                });
+            children.label(localization.get("H: close   Mouse wheel: scroll"));
+        })
+        .id();
+    actions_frozen.freeze(FreezeReason::Pause, panel);
+}
+
+fn spawn_entries(children: &mut ChildBuilder, history: &DialogueHistory, l10n: &Localization) {
+    if history.entries.is_empty() {
+        children.label(l10n.get("Nothing was said yet."));
+        return;
+    }
+    let mut run = None;
+    for entry in &history.entries {
+        if run != Some(entry.run) {
+            run = Some(entry.run);
+            let title = if entry.run == history.runs {
+                l10n.get("This run").to_string()
+            } else {
+                format!("{} {}", l10n.get("Run"), entry.run)
+            };
+            children.label(format!("- {title} -"));
+        }
+        let speaker = entry.speaker.as_deref().unwrap_or(l10n.get("Caveman"));
+        let text = if entry.chosen {
+            format!("{speaker}: > {}", entry.text)
+        } else {
+            format!("{speaker}: {}", entry.text)
+        };
+        children.label(text).insert(Style {
+            width: Px(PANEL_SIZE.x - 20.0),
+            ..default()
+        });
+    }
+}
+
+fn close_history(
+    mut commands: Commands,
+    panel: Query<Entity, With<HistoryPanel>>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) {
+    for entity in &panel {
+        commands.entity(entity).despawn_recursive();
+        actions_frozen.unfreeze(FreezeReason::Pause, entity);
+    }
+}
+
+/// Scroll with the mouse wheel or the arrow keys. The list is aligned to the
+/// bottom of the viewport, so it starts at the newest entry.
+fn scroll_history(
+    mut wheel: EventReader<MouseWheel>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut list: Query<(&mut HistoryList, &mut Style, &Node, &Parent)>,
+    viewports: Query<&Node>,
+) {
+    let mut delta = wheel
+        .read()
+        .map(|event| event.y * SCROLL_LINE_HEIGHT)
+        .sum::<f32>();
+    if input.pressed(KeyCode::ArrowUp) {
+        delta += SCROLL_LINE_HEIGHT / 4.0;
+    }
+    if input.pressed(KeyCode::ArrowDown) {
+        delta -= SCROLL_LINE_HEIGHT / 4.0;
+    }
+    for (mut list, mut style, node, parent) in &mut list {
+        let Ok(viewport) = viewports.get(parent.get()) else {
+            continue;
+        };
+        let max_offset = (node.size().y - viewport.size().y + 20.0).max(0.0);
+        list.offset = (list.offset + delta).clamp(0.0, max_offset);
+        style.top = Px(list.offset);
+    }
+}
@@ -1,72 +1,178 @@
+use std::sync::Arc;
+
 use bevy::{
-    color::palettes::css::BLACK,
-    input::{
-        common_conditions::input_just_pressed,
-        keyboard::{Key, KeyboardInput},
-    },
+    asset::LoadContext,
     math::bounding::{Aabb2d, IntersectsVolume},
     prelude::*,
-    render::primitives::Aabb,
+    render::{
+        primitives::Aabb,
+        texture::{ImageLoaderSettings, ImageSampler},
+    },
 };
-use bevy_yarnspinner::prelude::{DialogueRunner, YarnValue};
+use bevy_yarnspinner::prelude::DialogueRunner;
 use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    level::{Level, LevelAssets},
-    movement::ActionsFrozen,
+    level::Level,
+    movement::{ActionsFrozen, FreezeReason},
+    paper::OpenPaper,
     player::{Player, PlayerAssets},
+    recipe::{pickup_node, use_node},
+};
+use crate::{
+    asset_tracking::LoadResource, audio::SoundEffect, definitions::Definitions,
+    localization::Localization, screens::Screen, theme::prelude::*,
 };
-use crate::{audio::SoundEffect, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Inventory>();
     app.init_resource::<Inventory>();
+    app.load_definitions::<ItemDefinitions>();
 
     app.add_systems(
         Update,
         ((
             pick_up,
-            update_paper_text,
             update_inventory.run_if(resource_changed::<Inventory>),
         )
             .run_if(in_state(Screen::Gameplay)),),
     );
-    app.add_systems(
-        Update,
-        close_paper
-            .run_if(in_state(Screen::Gameplay).and_then(input_just_pressed(KeyCode::Escape))),
-    );
-    app.observe(open_paper);
 
     app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
         commands.insert_resource(Inventory::default())
     });
+    app.observe(toast_pickup);
+}
+
+/// An item, by the id Yarn refers to it with, e.g. `Banana`. What an item
+/// is called and looks like is defined in [`ItemDefinitions`].
+#[derive(
+    Component,
+    Reflect,
+    Debug,
+    Display,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[reflect_value(Debug, PartialEq, Hash)]
+#[serde(transparent)]
+pub struct Item(Arc<str>);
+
+impl Item {
+    /// Ids of the items of the base game that the game itself handles.
+    pub const PAPYRUS: &'static str = "Papyrus";
+    pub const KNIFE: &'static str = "Knife";
+    pub const PAPYRUS_STRIPS: &'static str = "PapyrusStrips";
+    pub const WOVEN_PAPYRUS: &'static str = "WovenPapyrus";
+    pub const PAPER: &'static str = "Paper";
+    pub const WRITTEN_PAPER: &'static str = "WrittenPaper";
+    pub const BANANA: &'static str = "Banana";
+    pub const BURNT_BANANA: &'static str = "BurntBanana";
+
+    /// The item with the given id, whether it is defined or not.
+    pub fn new(id: &str) -> Self {
+        Item(id.into())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+/// What the items are called and look like, from the `*.items.ron` files in
+/// the `items` folder. Mods can add items, and replace items of the base
+/// game by defining them again.
+#[derive(Resource, Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct ItemDefinitions {
+    pub items: Vec<ItemDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub id: Item,
+    /// The name shown to the player, in English.
+    pub name: String,
+    /// Path of the sprite in the level and in the inventory, relative to the
+    /// assets folder.
+    pub image: String,
+    /// Height of the sprite in the inventory bar, in pixels.
+    #[serde(default = "default_slot_height")]
+    pub slot_height: f32,
+    #[serde(skip)]
+    pub image_handle: Handle<Image>,
 }
 
-#[derive(Component, Reflect, Debug, Display, Clone, Copy, PartialEq, Eq)]
-pub enum Item {
-    Papyrus,
-    Knife,
-    PapyrusStrips,
-    WovenPapyrus,
-    Paper,
-    WrittenPaper,
-    Banana,
-    BurntBanana,
+fn default_slot_height() -> f32 {
+    50.0
+}
+
+impl ItemDefinitions {
+    pub fn get(&self, item: &Item) -> Option<&ItemDefinition> {
+        self.items.iter().find(|definition| definition.id == *item)
+    }
+
+    /// Look up a defined item by the id Yarn uses for it.
+    pub fn find(&self, id: &str) -> Option<Item> {
+        self.items
+            .iter()
+            .find(|definition| definition.id.id() == id)
+            .map(|definition| definition.id.clone())
+    }
+
+    /// Every defined item.
+    pub fn ids(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().map(|definition| &definition.id)
+    }
+
+    /// The name shown to the player, in English. Undefined items go by their
+    /// id.
+    pub fn name<'a>(&'a self, item: &'a Item) -> &'a str {
+        self.get(item)
+            .map_or(item.id(), |definition| definition.name.as_str())
+    }
+
+    pub fn image(&self, item: &Item) -> Handle<Image> {
+        self.get(item)
+            .map(|definition| definition.image_handle.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn slot_height(&self, item: &Item) -> f32 {
+        self.get(item)
+            .map_or_else(default_slot_height, |definition| definition.slot_height)
+    }
 }
 
-impl From<&str> for Item {
-    fn from(name: &str) -> Self {
-        match name {
-            "Papyrus" => Item::Papyrus,
-            "Knife" => Item::Knife,
-            "PapyrusStrips" => Item::PapyrusStrips,
-            "WovenPapyrus" => Item::WovenPapyrus,
-            "Paper" => Item::Paper,
-            "WrittenPaper" => Item::WrittenPaper,
-            "Banana" => Item::Banana,
-            "BurntBanana" => Item::BurntBanana,
-            _ => panic!("unknown item {}", name),
+impl Definitions for ItemDefinitions {
+    const FOLDER: &'static str = "items";
+    const EXTENSION: &'static str = "items.ron";
+    const FILES: &'static [&'static str] = &["items/game.items.ron"];
+
+    fn extend(&mut self, other: Self) {
+        for definition in other.items {
+            match self.items.iter_mut().find(|x| x.id == definition.id) {
+                Some(existing) => *existing = definition,
+                None => self.items.push(definition),
+            }
+        }
+    }
+
+    fn load_dependencies(&mut self, load_context: &mut LoadContext) {
+        for definition in &mut self.items {
+            // Keep the pixel art crisp.
+            definition.image_handle = load_context
+                .loader()
+                .with_settings(|settings: &mut ImageLoaderSettings| {
+                    settings.sampler = ImageSampler::nearest();
+                })
+                .load(definition.image.clone());
         }
     }
 }
@@ -77,13 +183,41 @@ pub struct Inventory {
     pub items: Vec<Item>,
 }
 
+impl Inventory {
+    /// Whether an item with the given id is in the inventory.
+    pub fn contains(&self, id: &str) -> bool {
+        self.items.iter().any(|item| item.id() == id)
+    }
+}
+
+/// Event triggered when the player picks up an item from the level.
+#[derive(Event, Debug)]
+pub struct ItemPickedUp(pub Item);
+
+/// Event triggered when an item is dropped from the inventory into the level.
+#[derive(Event, Debug)]
+pub struct ItemDropped(pub Item);
+
+fn toast_pickup(
+    trigger: Trigger<ItemPickedUp>,
+    mut commands: Commands,
+    items: Res<ItemDefinitions>,
+    localization: Res<Localization>,
+) {
+    let item = &trigger.event().0;
+    commands.trigger(
+        Toast::new(format!("+ {}", localization.get(items.name(item))))
+            .with_icon(items.image(item)),
+    );
+}
+
 fn pick_up(
     // mut gizmos: Gizmos,
     mut commands: Commands,
     input: Res<ButtonInput<KeyCode>>,
     player: Query<(&Aabb, &Transform), With<Player>>,
     items: Query<(&Aabb, &Transform, &Item)>,
-    mut dialogue_runner: Query<&mut DialogueRunner>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
     mut inventory: ResMut<Inventory>,
     mut level: ResMut<Level>,
     player_assets: Res<PlayerAssets>,
@@ -106,7 +240,8 @@ fn pick_up(
                 if !input.just_pressed(KeyCode::KeyE) {
                     return;
                 }
-                inventory.items.push(*item);
+                inventory.items.push(item.clone());
+                commands.trigger(ItemPickedUp(item.clone()));
 
                 if let Some(index) = level.items.iter().position(|x| x == item) {
                     level.items.remove(index);
@@ -121,7 +256,7 @@ fn pick_up(
                     Name::from("Pickup sound"),
                 ));
 
-                let mut dialogue_runner = dialogue_runner
+                let (runner, mut dialogue_runner) = dialogue_runner
                     .get_single_mut()
                     .expect("only one dialogue runner");
 
@@ -130,9 +265,9 @@ fn pick_up(
                     .set(format!("$_has_{}", item), true.into())
                     .unwrap();
 
-                if item == &Item::Paper {
-                    dialogue_runner.start_node("CollectedPaper");
-                    actions_frozen.freeze();
+                if let Some(node) = pickup_node(item) {
+                    dialogue_runner.start_node(node);
+                    actions_frozen.freeze(FreezeReason::Dialogue, runner);
                 }
             }
         }
@@ -143,7 +278,7 @@ fn update_inventory(
     mut commands: Commands,
     mut entity: Local<Option<Entity>>,
     inventory: Res<Inventory>,
-    level_assets: Res<LevelAssets>,
+    items: Res<ItemDefinitions>,
 ) {
     if let Some(ecommands) = entity.and_then(|e| commands.get_entity(e)) {
         ecommands.despawn_recursive();
@@ -154,19 +289,9 @@ fn update_inventory(
             .insert(StateScoped(Screen::Gameplay))
             .with_children(|children| {
                 for item in &inventory.items {
-                    let (image, height) = match item {
-                        Item::Knife => (level_assets.knife.clone(), 15.0),
-                        Item::Papyrus => (level_assets.papyrus.clone(), 60.0),
-                        Item::PapyrusStrips => (level_assets.papyrus_strips.clone(), 50.0),
-                        Item::WovenPapyrus => (level_assets.woven_papyrus.clone(), 50.0),
-                        Item::Paper => (level_assets.paper.clone(), 50.0),
-                        Item::WrittenPaper => (level_assets.written_paper.clone(), 50.0),
-                        Item::Banana => (level_assets.banana.clone(), 44.0),
-                        Item::BurntBanana => (level_assets.burnt_banana.clone(), 44.0),
-                    };
                     children
-                        .inventory_item(image, height)
-                        .insert(*item)
+                        .inventory_item(items.image(item), items.slot_height(item))
+                        .insert(item.clone())
                         .observe(interact_item);
                 }
             })
@@ -179,7 +304,7 @@ fn interact_item(
     mut commands: Commands,
     mut actions_frozen: ResMut<ActionsFrozen>,
     items: Query<&Item>,
-    mut dialogue_runner: Query<&mut DialogueRunner>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
 ) {
     if actions_frozen.is_frozen() {
         return;
@@ -187,166 +312,16 @@ fn interact_item(
     let item = items
         .get(trigger.entity())
         .expect("item was inserted on button");
-    let mut dialogue_runner = dialogue_runner
+    let (runner, mut dialogue_runner) = dialogue_runner
         .get_single_mut()
         .expect("only one dialogue runner");
 
-    if item == &Item::Paper {
-        commands.trigger(OpenPaper);
-    } else {
-        dialogue_runner.start_node(item.to_string());
-        actions_frozen.freeze();
+    if item.id() == Item::PAPER {
+        commands.trigger(OpenPaper::Write);
+    } else if item.id() == Item::WRITTEN_PAPER {
+        commands.trigger(OpenPaper::Read);
+    } else if let Some(node) = use_node(item) {
+        dialogue_runner.start_node(node);
+        actions_frozen.freeze(FreezeReason::Dialogue, runner);
     }
 }
-
-#[derive(Event, Debug)]
-struct OpenPaper;
-
-#[derive(Reflect, Component, Debug)]
-struct Paper;
-
-#[derive(Reflect, Component, Debug)]
-struct PaperText;
-
-fn open_paper(
-    _: Trigger<OpenPaper>,
-    mut commands: Commands,
-    mut actions_frozen: ResMut<ActionsFrozen>,
-    player_assets: Res<PlayerAssets>,
-) {
-    if actions_frozen.is_frozen() {
-        return;
-    }
-
-    commands.spawn((
-        Name::new("Paper"),
-        Paper,
-        SpriteBundle {
-            texture: player_assets.paper_big.clone(),
-            transform: Transform::from_scale(Vec2::splat(7.0).extend(1.0))
-                .with_translation(Vec3::new(0.0, 0.0, 70.0)),
-            ..Default::default()
-        },
-        StateScoped(Screen::Gameplay),
-    ));
-    commands.spawn((
-        Name::new("Paper Text"),
-        Text2dBundle {
-            text: Text::from_section(
-                "",
-                TextStyle {
-                    font: player_assets.animal_font.clone(),
-                    font_size: 80.0,
-                    color: BLACK.into(),
-                },
-            ),
-            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 80.0)),
-            ..default()
-        },
-        PaperText,
-    ));
-    actions_frozen.freeze();
-}
-
-fn update_paper_text(
-    mut events: EventReader<KeyboardInput>,
-    dialogue_runner: Query<&DialogueRunner>,
-    mut text: Query<&mut Text, With<PaperText>>,
-    player_assets: Res<PlayerAssets>,
-) {
-    let dialogue_runner = dialogue_runner
-        .get_single()
-        .expect("only one dialogue runner");
-
-    let learned_pen = dialogue_runner
-        .variable_storage()
-        .get("$learned_pen")
-        .unwrap_or(YarnValue::Boolean(false));
-    if learned_pen != YarnValue::Boolean(true) {
-        return;
-    }
-
-    let Ok(mut text) = text.get_single_mut() else {
-        return;
-    };
-    for event in events.read() {
-        // Only trigger changes when the key is first pressed.
-        if !event.state.is_pressed() {
-            continue;
-        }
-
-        let max_width = 6;
-        let max_height = 4;
-        match &event.logical_key {
-            Key::Character(character) => {
-                if text.sections.last().unwrap().value.len() >= max_width
-                    && text.sections.len() < max_height
-                {
-                    text.sections.last_mut().unwrap().value.push('\n');
-                    text.sections.push(TextSection::new(
-                        "",
-                        TextStyle {
-                            font: player_assets.animal_font.clone(),
-                            font_size: 80.0,
-                            color: BLACK.into(),
-                        },
-                    ));
-                }
-                if let Some(text) = text.sections.last_mut() {
-                    if text.value.len() < max_width {
-                        text.value.push_str(character);
-                    }
-                }
-            }
-            _ => continue,
-        }
-    }
-}
-
-fn close_paper(
-    mut commands: Commands,
-    paper: Query<Entity, With<Paper>>,
-    paper_text: Query<(Entity, &Text), With<PaperText>>,
-    mut actions_frozen: ResMut<ActionsFrozen>,
-    mut dialogue_runner: Query<&mut DialogueRunner>,
-    mut inventory: ResMut<Inventory>,
-    player_assets: Res<PlayerAssets>,
-) {
-    for entity in &paper {
-        commands.entity(entity).despawn_recursive();
-        actions_frozen.unfreeze();
-    }
-    let mut written = false;
-    for (entity, text) in &paper_text {
-        if !text.sections[0].value.is_empty() {
-            written = true;
-        }
-        commands.entity(entity).despawn_recursive();
-    }
-    if !written {
-        return;
-    }
-
-    let from = Item::Paper;
-    let to = Item::WrittenPaper;
-    let index = inventory.items.iter().position(|x| *x == from).unwrap();
-    inventory.items.remove(index);
-    inventory.items.push(to);
-
-    let mut dialogue_runner = dialogue_runner
-        .get_single_mut()
-        .expect("only one dialogue runner");
-    let vars = dialogue_runner.variable_storage_mut();
-
-    vars.set(format!("$_has_{}", from), false.into()).unwrap();
-    vars.set(format!("$_has_{}", to), true.into()).unwrap();
-
-    commands.spawn((
-        AudioBundle {
-            source: player_assets.item_pickup.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        },
-        SoundEffect,
-        Name::from("Convert sound"),
-    ));
-}
@@ -0,0 +1,242 @@
+//! Items fly from the level into their inventory slot when they are picked
+//! up, and back to where they lie when they are dropped. The item only shows
+//! up at its destination once it landed, or right away if its slot doesn't
+//! show up in time.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, render::view::VisibilitySystems};
+use bevy_tweening::{
+    lens::{TransformPositionLens, TransformScaleLens},
+    Animator, EaseFunction, Tracks, Tween, TweenCompleted,
+};
+
+use super::{
+    inventory::{Item, ItemDefinitions, ItemDropped, ItemPickedUp},
+    level::{AreaLayouts, ITEM_SCALE},
+};
+use crate::{
+    screens::{Area, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (launch_pickup_flights, land_flights)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    // After the inventory bar and the item sprites were rebuilt, so they
+    // never show up early for a frame.
+    app.add_systems(
+        PostUpdate,
+        hide_flight_destinations
+            .before(VisibilitySystems::VisibilityPropagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(fly_to_inventory);
+    app.observe(fly_to_level);
+}
+
+const FLIGHT_DURATION: Duration = Duration::from_millis(450);
+/// Flying items are drawn above everything else in the level.
+const FLIGHT_Z: f32 = 100.0;
+/// Scale of an item sprite the size of its inventory slot, relative to how
+/// big it is in the level.
+const SLOT_SCALE: f32 = 0.6;
+/// User data of the [`TweenCompleted`] event sent once an item landed.
+const FLIGHT_LANDED: u64 = 2;
+/// How long a picked up item waits for its inventory slot before it gives up
+/// and the item just shows up in the inventory.
+const SLOT_WAIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Component, Debug)]
+struct ItemFlight {
+    item: Item,
+    destination: Destination,
+}
+
+/// A picked up item that is waiting for its inventory slot to be laid out.
+#[derive(Component, Debug)]
+struct WaitingForSlot(Timer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Destination {
+    Inventory,
+    Level,
+}
+
+/// Start flying from where the item lies. Its slot doesn't exist until the
+/// inventory bar is rebuilt, so the flight is launched once it does.
+fn fly_to_inventory(
+    trigger: Trigger<ItemPickedUp>,
+    mut commands: Commands,
+    items: Query<(&Item, &Transform), With<Sprite>>,
+    area: Res<State<Area>>,
+    definitions: Res<ItemDefinitions>,
+) {
+    let item = &trigger.event().0;
+    let Some((_, transform)) = items.iter().find(|(other, _)| *other == item) else {
+        return;
+    };
+    commands.spawn((
+        Name::new(format!("{item} Flight")),
+        ItemFlight {
+            item: item.clone(),
+            destination: Destination::Inventory,
+        },
+        SpriteBundle {
+            texture: definitions.image(item),
+            transform: transform
+                .with_translation(transform.translation.truncate().extend(FLIGHT_Z)),
+            ..default()
+        },
+        WaitingForSlot(Timer::new(SLOT_WAIT_TIMEOUT, TimerMode::Once)),
+        StateScoped(*area.get()),
+    ));
+}
+
+/// Fly from the slot the item is leaving to where it lies in the area. Items
+/// that have no place in the current area just vanish from the inventory.
+fn fly_to_level(
+    trigger: Trigger<ItemDropped>,
+    mut commands: Commands,
+    slots: Query<(&Item, &Node, &GlobalTransform), With<Button>>,
+    camera: Query<(&Camera, &GlobalTransform), With<IsDefaultUiCamera>>,
+    area: Res<State<Area>>,
+    layouts: Res<AreaLayouts>,
+    definitions: Res<ItemDefinitions>,
+) {
+    let item = &trigger.event().0;
+    let Some(placement) = layouts
+        .of(*area.get())
+        .items
+        .iter()
+        .find(|placement| placement.item == *item)
+    else {
+        return;
+    };
+    let Some(start) = slot_translation(item, &slots, &camera) else {
+        return;
+    };
+    let rotation = Quat::from_rotation_z(placement.rotation.to_radians());
+    let from = Transform::from_translation(start.extend(FLIGHT_Z))
+        .with_scale(Vec3::splat(ITEM_SCALE * SLOT_SCALE))
+        .with_rotation(rotation);
+    let to = Transform::from_translation(placement.translation.truncate().extend(FLIGHT_Z))
+        .with_scale(Vec3::splat(ITEM_SCALE))
+        .with_rotation(rotation);
+    commands.spawn((
+        Name::new(format!("{item} Flight")),
+        ItemFlight {
+            item: item.clone(),
+            destination: Destination::Level,
+        },
+        SpriteBundle {
+            texture: definitions.image(item),
+            transform: from,
+            ..default()
+        },
+        Animator::new(flight_tween(&from, &to)),
+        StateScoped(*area.get()),
+    ));
+}
+
+fn launch_pickup_flights(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flights: Query<(Entity, &ItemFlight, &Transform, &mut WaitingForSlot)>,
+    slots: Query<(&Item, &Node, &GlobalTransform), With<Button>>,
+    camera: Query<(&Camera, &GlobalTransform), With<IsDefaultUiCamera>>,
+) {
+    for (entity, flight, from, mut waiting) in &mut flights {
+        let Some(end) = slot_translation(&flight.item, &slots, &camera) else {
+            if waiting.0.tick(time.delta()).finished() {
+                commands.entity(entity).despawn_recursive();
+            }
+            continue;
+        };
+        let to = from
+            .with_translation(end.extend(FLIGHT_Z))
+            .with_scale(from.scale * SLOT_SCALE);
+        commands
+            .entity(entity)
+            .remove::<WaitingForSlot>()
+            .insert(Animator::new(flight_tween(from, &to)));
+    }
+}
+
+fn flight_tween(from: &Transform, to: &Transform) -> Tracks<Transform> {
+    Tracks::new([
+        Tween::new(
+            EaseFunction::QuadraticInOut,
+            FLIGHT_DURATION,
+            TransformPositionLens {
+                start: from.translation,
+                end: to.translation,
+            },
+        )
+        .with_completed_event(FLIGHT_LANDED),
+        Tween::new(
+            EaseFunction::QuadraticInOut,
+            FLIGHT_DURATION,
+            TransformScaleLens {
+                start: from.scale,
+                end: to.scale,
+            },
+        ),
+    ])
+}
+
+/// Where the inventory slot of the item is in the world, once it was laid out.
+fn slot_translation(
+    item: &Item,
+    slots: &Query<(&Item, &Node, &GlobalTransform), With<Button>>,
+    camera: &Query<(&Camera, &GlobalTransform), With<IsDefaultUiCamera>>,
+) -> Option<Vec2> {
+    let (camera, camera_transform) = camera.get_single().ok()?;
+    let (_, _, slot_transform) = slots
+        .iter()
+        .find(|(other, node, _)| *other == item && node.size() != Vec2::ZERO)?;
+    camera.viewport_to_world_2d(camera_transform, slot_transform.translation().truncate())
+}
+
+fn land_flights(
+    mut commands: Commands,
+    mut events: EventReader<TweenCompleted>,
+    flights: Query<(), With<ItemFlight>>,
+) {
+    for event in events.read() {
+        if event.user_data == FLIGHT_LANDED && flights.contains(event.entity) {
+            commands.entity(event.entity).despawn_recursive();
+        }
+    }
+}
+
+/// Hide the inventory slots and item sprites that items are still flying to.
+fn hide_flight_destinations(
+    flights: Query<&ItemFlight>,
+    mut slots: Query<(&Item, &mut Visibility), With<Button>>,
+    mut sprites: Query<(&Item, &mut Visibility), (With<Sprite>, Without<Button>)>,
+) {
+    let flying = |item: &Item, destination: Destination| {
+        flights
+            .iter()
+            .any(|flight| flight.item == *item && flight.destination == destination)
+    };
+    let visibility = |hidden: bool| {
+        if hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        }
+    };
+    for (item, mut slot_visibility) in &mut slots {
+        slot_visibility.set_if_neq(visibility(flying(item, Destination::Inventory)));
+    }
+    for (item, mut sprite_visibility) in &mut sprites {
+        sprite_visibility.set_if_neq(visibility(flying(item, Destination::Level)));
+    }
+}
@@ -0,0 +1,308 @@
+//! The objectives of the current run. The current one is shown at the top of
+//! the screen, and all of them in the journal. Press J to show it.
+//!
+//! Dialogue adds and completes objectives with `<<objective_add>>` and
+//! `<<objective_complete>>`, the items the caveman holds complete the rest.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy_yarnspinner::prelude::DialogueRunner;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    inventory::{Inventory, Item},
+    level::{AreaLayouts, Level},
+    movement::{ActionsFrozen, FreezeReason},
+};
+use crate::{
+    localization::Localization,
+    persistence::{PersistResource, Persistent},
+    puzzle::{atoms, held_atom, PuzzleGraph},
+    screens::Screen,
+    theme::prelude::*,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.persist_resource::<Journal>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_journal, spawn_objective_display),
+    );
+    app.add_systems(
+        Update,
+        (
+            track_items.run_if(
+                resource_changed::<Inventory>.or_else(resource_exists_and_changed::<PuzzleGraph>),
+            ),
+            update_objective_display
+                .run_if(resource_changed::<Journal>.or_else(resource_changed::<Localization>)),
+            toggle_journal.run_if(input_just_pressed(KeyCode::KeyJ)),
+            close_journal.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    AskWife,
+    FindPlant,
+    CutPlant,
+    GetCloth,
+    WriteNote,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 5] = [
+        Objective::AskWife,
+        Objective::FindPlant,
+        Objective::CutPlant,
+        Objective::GetCloth,
+        Objective::WriteNote,
+    ];
+
+    /// The id Yarn commands use for the objective.
+    pub fn id(self) -> &'static str {
+        match self {
+            Objective::AskWife => "ask_wife",
+            Objective::FindPlant => "find_plant",
+            Objective::CutPlant => "cut_plant",
+            Objective::GetCloth => "get_cloth",
+            Objective::WriteNote => "write_note",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Objective::ALL
+            .into_iter()
+            .find(|objective| objective.id() == id)
+    }
+
+    /// What completes the objective, as named in the [`PuzzleGraph`].
+    pub fn goal(self) -> String {
+        match self {
+            Objective::AskWife => "$asked_for_plant".to_string(),
+            Objective::FindPlant => held_atom(Item::PAPYRUS),
+            Objective::CutPlant => held_atom(Item::PAPYRUS_STRIPS),
+            Objective::GetCloth => held_atom(Item::WOVEN_PAPYRUS),
+            Objective::WriteNote => held_atom(Item::WRITTEN_PAPER),
+        }
+    }
+
+    /// What the caveman has to do, in English.
+    pub fn description(self) -> &'static str {
+        match self {
+            Objective::AskWife => "Ask wife what to hunt",
+            Objective::FindPlant => "Hunt a plant",
+            Objective::CutPlant => "Cut the plant into strips",
+            Objective::GetCloth => "Get the strips made into cloth",
+            Objective::WriteNote => "Write something nice for wife",
+        }
+    }
+}
+
+/// The objectives of the current run, in the order they were added.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Persistent for Journal {
+    const KEY: &'static str = "journal";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub objective: Objective,
+    pub completed: bool,
+}
+
+impl Journal {
+    /// Add an objective, unless it is already in the journal.
+    pub fn add(&mut self, objective: Objective) {
+        if !self.contains(objective) {
+            self.entries.push(JournalEntry {
+                objective,
+                completed: false,
+            });
+        }
+    }
+
+    /// Complete an objective, adding it first if the caveman got ahead of it.
+    pub fn complete(&mut self, objective: Objective) {
+        self.add(objective);
+        for entry in &mut self.entries {
+            if entry.objective == objective {
+                entry.completed = true;
+            }
+        }
+    }
+
+    pub fn contains(&self, objective: Objective) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.objective == objective)
+    }
+
+    /// The newest objective that isn't completed yet.
+    pub fn current(&self) -> Option<Objective> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| !entry.completed)
+            .map(|entry| entry.objective)
+    }
+}
+
+fn reset_journal(mut journal: ResMut<Journal>) {
+    *journal = Journal::default();
+}
+
+/// Holding an item completes the objectives it comes after in the puzzle,
+/// and adds the ones it is directly needed for once the caveman has
+/// everything else they need.
+fn track_items(
+    inventory: Res<Inventory>,
+    level: Res<Level>,
+    layouts: Res<AreaLayouts>,
+    graph: Option<Res<PuzzleGraph>>,
+    dialogue_runner: Query<&DialogueRunner>,
+    mut journal: ResMut<Journal>,
+) {
+    // The puzzle is still being searched.
+    let Some(graph) = graph else {
+        return;
+    };
+    let Ok(dialogue_runner) = dialogue_runner.get_single() else {
+        return;
+    };
+    let got = atoms(
+        &inventory.items,
+        &level.items,
+        &layouts.start_items(),
+        &dialogue_runner.variable_storage().variables(),
+    );
+    for item in &inventory.items {
+        let held = held_atom(item.id());
+        for objective in Objective::ALL {
+            let goal = objective.goal();
+            if goal == held || graph.leads_to(&goal, &held) {
+                journal.complete(objective);
+            } else if graph.direct_needs(&goal).contains(&held.as_str())
+                && graph.can_get(&goal, &got)
+            {
+                journal.add(objective);
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct ObjectiveDisplay;
+
+fn spawn_objective_display(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Objective Display"),
+            NodeBundle {
+                style: Style {
+                    width: Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    top: Px(10.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            children.spawn((
+                Name::new("Objective Text"),
+                ObjectiveDisplay,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: UI_FONT,
+                        font_size: 24.0,
+                        color: ui_palette::LABEL_TEXT,
+                    },
+                ),
+            ));
+        });
+}
+
+fn update_objective_display(
+    journal: Res<Journal>,
+    localization: Res<Localization>,
+    mut display: Query<&mut Text, With<ObjectiveDisplay>>,
+) {
+    let text = journal.current().map_or_else(String::new, |objective| {
+        format!(
+            "{}: {}",
+            localization.get("Objective"),
+            localization.get(objective.description())
+        )
+    });
+    for mut display in &mut display {
+        display.sections[0].value.clone_from(&text);
+    }
+}
+
+#[derive(Component)]
+struct JournalPanel;
+
+fn toggle_journal(
+    mut commands: Commands,
+    panel: Query<Entity, With<JournalPanel>>,
+    journal: Res<Journal>,
+    localization: Res<Localization>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) {
+    if let Ok(entity) = panel.get_single() {
+        commands.entity(entity).despawn_recursive();
+        actions_frozen.unfreeze(FreezeReason::Pause, entity);
+        return;
+    }
+    if actions_frozen.is_frozen() {
+        return;
+    }
+    let panel = commands
+        .ui_root()
+        .insert((
+            Name::new("Journal Panel"),
+            JournalPanel,
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            children.header(localization.get("Journal"));
+            if journal.entries.is_empty() {
+                children.label(localization.get("Nothing to do yet."));
+            }
+            for entry in &journal.entries {
+                let mark = if entry.completed { "[x]" } else { "[ ]" };
+                children.label(format!(
+                    "{mark} {}",
+                    localization.get(entry.objective.description())
+                ));
+            }
+            children.label("");
+            children.label(localization.get("J: close"));
+        })
+        .id();
+    actions_frozen.freeze(FreezeReason::Pause, panel);
+}
+
+fn close_journal(
+    mut commands: Commands,
+    panel: Query<Entity, With<JournalPanel>>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) {
+    for entity in &panel {
+        commands.entity(entity).despawn_recursive();
+        actions_frozen.unfreeze(FreezeReason::Pause, entity);
+    }
+}
@@ -1,100 +1,45 @@
 //! Spawn the main level.
 
+use std::collections::HashMap;
+
 use bevy::{
     ecs::{system::RunSystemOnce, world::Command},
     prelude::*,
-    render::texture::{ImageLoaderSettings, ImageSampler},
 };
+use serde::Deserialize;
 
 use crate::{
-    asset_tracking::LoadResource,
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    definitions::Definitions,
     screens::{Area, Screen},
 };
 
-use super::{inventory::Item, wife::spawn_wife};
+use super::{
+    inventory::{Item, ItemDefinitions},
+    trigger::TriggerZone,
+    wife::spawn_wife,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<LevelAssets>();
+    app.load_collection::<LevelAssets>();
+    app.load_definitions::<AreaLayouts>();
     app.register_type::<Level>();
     app.init_resource::<Level>();
 
-    app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
-        commands.insert_resource(Level::default())
-    });
-
     app.add_systems(
-        OnEnter(Area::Cave),
-        |mut commands: Commands, level: Res<Level>, items: Query<(Entity, &Item), With<Sprite>>| {
-            commands.add(|w: &mut World| {
-                SpawnBackground { area: Area::Cave }.apply(w);
-                w.run_system_once(spawn_wife);
-            });
-
-            for (item, translation, rotation) in [
-                (
-                    Item::Knife,
-                    Vec3::new(295.0, -130.0, -25.0),
-                    180.0f32.to_radians(),
-                ),
-                (Item::BurntBanana, Vec3::new(0.0, -130.0, 55.0), 0.0),
-            ] {
-                let spawned = items.iter().find(|(_, i)| i == &&item).map(|(e, _)| e);
-                let should_have = level.items.contains(&item);
-                if spawned.is_none() && should_have {
-                    commands.add(move |w: &mut World| {
-                        SpawnItem {
-                            item,
-                            transform: Transform::from_translation(translation)
-                                .with_scale(Vec3::splat(8.0))
-                                .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, rotation)),
-                        }
-                        .apply(w);
-                    });
-                } else if !should_have {
-                    if let Some(entity) = spawned {
-                        commands.entity(entity).despawn_recursive();
-                    }
-                }
-            }
+        OnEnter(Screen::Gameplay),
+        |mut commands: Commands, layouts: Res<AreaLayouts>| {
+            commands.insert_resource(Level::new(&layouts))
         },
     );
 
-    app.add_systems(
-        Update,
-        (|mut commands: Commands,
-          level: Res<Level>,
-          items: Query<(Entity, &Item), With<Sprite>>| {
-            for (item, translation, rotation) in [
-                (
-                    Item::Knife,
-                    Vec3::new(295.0, -130.0, -25.0),
-                    180.0f32.to_radians(),
-                ),
-                (Item::BurntBanana, Vec3::new(0.0, -130.0, 55.0), 0.0),
-            ] {
-                let spawned = items.iter().find(|(_, i)| i == &&item).map(|(e, _)| e);
-                let should_have = level.items.contains(&item);
-                if spawned.is_none() && should_have {
-                    commands.add(move |w: &mut World| {
-                        SpawnItem {
-                            item,
-                            transform: Transform::from_translation(translation)
-                                .with_scale(Vec3::splat(8.0))
-                                .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, rotation)),
-                        }
-                        .apply(w);
-                    });
-                } else if !should_have {
-                    if let Some(entity) = spawned {
-                        commands.entity(entity).despawn_recursive();
-                    }
-                }
-            }
-        })
-        .run_if(resource_changed::<Level>)
-        .run_if(in_state(Area::Cave)),
-    );
-
+    app.add_systems(OnEnter(Area::Cave), |mut commands: Commands| {
+        commands.add(|w: &mut World| {
+            SpawnBackground { area: Area::Cave }.apply(w);
+            w.run_system_once(spawn_wife);
+        });
+    });
     app.add_systems(OnEnter(Area::Outside), |mut commands: Commands| {
         commands.add(|w: &mut World| {
             SpawnBackground {
@@ -103,219 +48,182 @@ pub(super) fn plugin(app: &mut App) {
             .apply(w);
         });
     });
+    app.add_systems(OnEnter(Area::Cave), (sync_items, spawn_trigger_zones));
+    app.add_systems(OnEnter(Area::Outside), (sync_items, spawn_trigger_zones));
 
     app.add_systems(
         Update,
-        (|mut commands: Commands,
-          level: Res<Level>,
-          items: Query<(Entity, &Item), With<Sprite>>| {
-            for (item, translation) in [
-                (Item::WovenPapyrus, Vec3::new(-300.0, -130.0, -25.0)),
-                (Item::Paper, Vec3::new(-300.0, -130.0, -25.0)),
-                (Item::Papyrus, Vec3::new(170.0, -62.0, -25.0)),
-                (Item::Banana, Vec3::new(440.0, -130.0, -25.0)),
-            ] {
-                let spawned = items.iter().find(|(_, i)| i == &&item).map(|(e, _)| e);
-                let should_have = level.items.contains(&item);
-                if spawned.is_none() && should_have {
-                    commands.add(move |w: &mut World| {
-                        SpawnItem {
-                            item,
-                            transform: Transform::from_translation(translation)
-                                .with_scale(Vec3::splat(8.0)),
-                        }
-                        .apply(w);
-                    });
-                } else if !should_have {
-                    if let Some(entity) = spawned {
-                        commands.entity(entity).despawn_recursive();
-                    }
-                }
-            }
-        })
-        .run_if(resource_changed::<Level>)
-        .run_if(in_state(Area::Outside)),
+        sync_items
+            .run_if(resource_exists::<State<Area>>)
+            .run_if(resource_changed::<Level>),
     );
+}
 
-    app.add_systems(
-        OnEnter(Area::Outside),
-        |mut commands: Commands, level: Res<Level>, items: Query<(Entity, &Item), With<Sprite>>| {
-            for (item, translation) in [
-                (Item::WovenPapyrus, Vec3::new(-300.0, -130.0, -25.0)),
-                (Item::Paper, Vec3::new(-300.0, -130.0, -25.0)),
-                (Item::Papyrus, Vec3::new(170.0, -62.0, -25.0)),
-                (Item::Banana, Vec3::new(440.0, -130.0, -25.0)),
-            ] {
-                let spawned = items.iter().find(|(_, i)| i == &&item).map(|(e, _)| e);
-                let should_have = level.items.contains(&item);
-                if spawned.is_none() && should_have {
-                    commands.add(move |w: &mut World| {
-                        SpawnItem {
-                            item,
-                            transform: Transform::from_translation(translation)
-                                .with_scale(Vec3::splat(8.0)),
-                        }
-                        .apply(w);
-                    });
-                } else if !should_have {
-                    if let Some(entity) = spawned {
-                        commands.entity(entity).despawn_recursive();
-                    }
+/// Everything that is placed in every [`Area`], from the `*.areas.ron` files
+/// in the `areas` folder. Mods add to the layouts of the base game.
+#[derive(Resource, Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct AreaLayouts {
+    pub areas: HashMap<Area, AreaLayout>,
+}
+
+impl AreaLayouts {
+    pub fn of(&self, area: Area) -> &AreaLayout {
+        const EMPTY: &AreaLayout = &AreaLayout {
+            items: Vec::new(),
+            trigger_zones: Vec::new(),
+            ducks: Vec::new(),
+        };
+        self.areas.get(&area).unwrap_or(EMPTY)
+    }
+
+    /// Every area with its layout.
+    pub fn iter(&self) -> impl Iterator<Item = (Area, &AreaLayout)> {
+        [Area::Cave, Area::Outside]
+            .into_iter()
+            .map(|area| (area, self.of(area)))
+    }
+
+    /// The items that lie in the areas when a run starts.
+    pub fn start_items(&self) -> Vec<Item> {
+        self.iter()
+            .flat_map(|(_, layout)| &layout.items)
+            .filter(|placement| placement.at_start)
+            .map(|placement| placement.item.clone())
+            .collect()
+    }
+}
+
+impl Definitions for AreaLayouts {
+    const FOLDER: &'static str = "areas";
+    const EXTENSION: &'static str = "areas.ron";
+    const FILES: &'static [&'static str] = &["areas/game.areas.ron"];
+
+    fn extend(&mut self, other: Self) {
+        for (area, layout) in other.areas {
+            let existing = self.areas.entry(area).or_default();
+            existing.items.extend(layout.items);
+            existing.trigger_zones.extend(layout.trigger_zones);
+            existing.ducks.extend(layout.ducks);
+        }
+    }
+}
+
+/// Everything that is placed in an [`Area`] on top of its background.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AreaLayout {
+    pub items: Vec<ItemPlacement>,
+    pub trigger_zones: Vec<TriggerZone>,
+    pub ducks: Vec<DuckPlacement>,
+}
+
+/// Scale of the item sprites lying in an area.
+pub const ITEM_SCALE: f32 = 8.0;
+
+/// Where an [`Item`] lies in an area while it is part of the [`Level`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemPlacement {
+    pub item: Item,
+    pub translation: Vec3,
+    /// Rotation around the z axis in degrees.
+    #[serde(default)]
+    pub rotation: f32,
+    /// Whether the item lies here when a run starts.
+    #[serde(default)]
+    pub at_start: bool,
+}
+
+/// Where a rubber duck is hidden in an area.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DuckPlacement {
+    /// The id found ducks are saved with, unique across areas.
+    pub id: String,
+    pub translation: Vec3,
+}
+
+/// Spawn or despawn item sprites of the current area so that they match the [`Level`].
+fn sync_items(
+    mut commands: Commands,
+    level: Res<Level>,
+    layouts: Res<AreaLayouts>,
+    area: Res<State<Area>>,
+    items: Query<(Entity, &Item), With<Sprite>>,
+) {
+    for placement in &layouts.of(*area.get()).items {
+        let item = &placement.item;
+        let spawned = items.iter().find(|(_, i)| i == &item).map(|(e, _)| e);
+        let should_have = level.items.contains(item);
+        if spawned.is_none() && should_have {
+            let placement = placement.clone();
+            commands.add(move |w: &mut World| {
+                SpawnItem {
+                    item: placement.item,
+                    transform: Transform::from_translation(placement.translation)
+                        .with_scale(Vec3::splat(ITEM_SCALE))
+                        .with_rotation(Quat::from_rotation_z(placement.rotation.to_radians())),
                 }
+                .apply(w);
+            });
+        } else if !should_have {
+            if let Some(entity) = spawned {
+                commands.entity(entity).despawn_recursive();
             }
-        },
-    );
+        }
+    }
+}
+
+fn spawn_trigger_zones(mut commands: Commands, layouts: Res<AreaLayouts>, area: Res<State<Area>>) {
+    for zone in &layouts.of(*area.get()).trigger_zones {
+        commands.spawn((
+            Name::new(format!("{} Trigger Zone", zone.name)),
+            zone.clone(),
+            StateScoped(*area.get()),
+        ));
+    }
 }
 
-#[derive(Resource, Reflect)]
+/// The items lying in the areas during a run.
+#[derive(Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct Level {
     pub items: Vec<Item>,
 }
 
-impl Default for Level {
-    fn default() -> Self {
+impl Level {
+    /// The level at the start of a run.
+    pub fn new(layouts: &AreaLayouts) -> Self {
         Self {
-            items: vec![Item::Papyrus, Item::Knife, Item::Banana],
+            items: layouts.start_items(),
         }
     }
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct LevelAssets {
-    #[dependency]
     pub cave_background: Handle<Image>,
-    #[dependency]
     pub cave_ground: Handle<Image>,
-    #[dependency]
     pub outside_background: Handle<Image>,
-    #[dependency]
     pub outside_ground: Handle<Image>,
-    #[dependency]
     pub palm_tree: Handle<Image>,
 
-    #[dependency]
-    pub knife: Handle<Image>,
-    #[dependency]
-    pub papyrus: Handle<Image>,
-    #[dependency]
-    pub papyrus_strips: Handle<Image>,
-    #[dependency]
-    pub woven_papyrus: Handle<Image>,
-    #[dependency]
-    pub paper: Handle<Image>,
-    #[dependency]
-    pub written_paper: Handle<Image>,
-    #[dependency]
-    pub banana: Handle<Image>,
-    #[dependency]
-    pub burnt_banana: Handle<Image>,
-
-    #[dependency]
+    pub ducky: Handle<Image>,
+
     pub dino_stomp: Handle<AudioSource>,
 }
 
-impl LevelAssets {
-    pub const PATH_CAVE_BACKGROUND: &'static str = "images/cave.png";
-    pub const PATH_CAVE_GROUND: &'static str = "images/cave_ground.png";
-    pub const PATH_OUTSIDE_BACKGROUND: &'static str = "images/outside.png";
-    pub const PATH_OUTSIDE_GROUND: &'static str = "images/outside_ground.png";
-    pub const PATH_PALM_TREE: &'static str = "images/palm_tree.png";
-    pub const PATH_PAPYRUS: &'static str = "images/papyrus.png";
-    pub const PATH_KNIFE: &'static str = "images/knife.png";
-    pub const PATH_WOVEN: &'static str = "images/papyrus_woven.png";
-    pub const PATH_PAPER: &'static str = "images/paper.png";
-    pub const PATH_WRITTEN_PAPER: &'static str = "images/paper_written.png";
-    pub const PATH_STRIPS: &'static str = "images/papyrus_strips.png";
-    pub const PATH_BANANA: &'static str = "images/banan.png";
-    pub const PATH_BURNT_BANANA: &'static str = "images/banan_burnt.png";
-    pub const PATH_DINO_STOMP: &'static str = "audio/sound_effects/stomp.ogg";
-}
+impl FromCollection for LevelAssets {
+    const COLLECTION: &'static str = "level";
 
-impl FromWorld for LevelAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            cave_background: assets.load_with_settings(
-                LevelAssets::PATH_CAVE_BACKGROUND,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            cave_ground: assets.load_with_settings(
-                LevelAssets::PATH_CAVE_GROUND,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            outside_background: assets.load_with_settings(
-                LevelAssets::PATH_OUTSIDE_BACKGROUND,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            outside_ground: assets.load_with_settings(
-                LevelAssets::PATH_OUTSIDE_GROUND,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            palm_tree: assets.load_with_settings(
-                LevelAssets::PATH_PALM_TREE,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            papyrus: assets.load_with_settings(
-                LevelAssets::PATH_PAPYRUS,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            papyrus_strips: assets.load_with_settings(
-                LevelAssets::PATH_STRIPS,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            knife: assets.load_with_settings(
-                LevelAssets::PATH_KNIFE,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            woven_papyrus: assets.load_with_settings(
-                LevelAssets::PATH_WOVEN,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            paper: assets.load_with_settings(
-                LevelAssets::PATH_PAPER,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            written_paper: assets.load_with_settings(
-                LevelAssets::PATH_WRITTEN_PAPER,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            banana: assets.load_with_settings(
-                LevelAssets::PATH_BANANA,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            burnt_banana: assets.load_with_settings(
-                LevelAssets::PATH_BURNT_BANANA,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            dino_stomp: assets.load(LevelAssets::PATH_DINO_STOMP),
-        }
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            cave_background: collection.image("cave_background")?,
+            cave_ground: collection.image("cave_ground")?,
+            outside_background: collection.image("outside_background")?,
+            outside_ground: collection.image("outside_ground")?,
+            palm_tree: collection.image("palm_tree")?,
+            ducky: collection.image("ducky")?,
+            dino_stomp: collection.audio("dino_stomp")?,
+        })
     }
 }
 
@@ -408,25 +316,17 @@ impl Command for SpawnItem {
 fn spawn_item(
     In(config): In<SpawnItem>,
     mut commands: Commands,
-    level_assets: Res<LevelAssets>,
+    items: Res<ItemDefinitions>,
     state: Res<State<Area>>,
 ) {
     commands.spawn((
         Name::new(config.item.to_string()),
-        config.item,
         SpriteBundle {
-            texture: match config.item {
-                Item::Papyrus => level_assets.papyrus.clone(),
-                Item::Knife => level_assets.knife.clone(),
-                Item::WovenPapyrus => level_assets.woven_papyrus.clone(),
-                Item::Paper => level_assets.paper.clone(),
-                Item::Banana => level_assets.banana.clone(),
-                Item::BurntBanana => level_assets.burnt_banana.clone(),
-                _ => todo!("no sprite for {}", config.item.to_string()),
-            },
+            texture: items.image(&config.item),
             transform: config.transform,
             ..Default::default()
         },
+        config.item,
         StateScoped(*state.get()),
     ));
 }
@@ -5,14 +5,31 @@
 
 use bevy::prelude::*;
 
+pub mod achievements;
 mod animation;
+pub mod difficulty;
 pub mod dino;
+pub mod drawing;
+pub mod ducks;
+pub mod ending;
 pub mod fire;
+pub mod ghost;
+pub mod hints;
+pub mod history;
 pub mod inventory;
+mod item_flight;
+pub mod journal;
 pub mod level;
 pub mod movement;
+pub mod npc;
+pub mod paper;
 pub mod player;
-mod wife;
+pub mod recipe;
+pub mod speech;
+pub mod speedrun;
+pub mod stats;
+pub mod trigger;
+pub mod wife;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -21,8 +38,26 @@ pub(super) fn plugin(app: &mut App) {
         player::plugin,
         level::plugin,
         inventory::plugin,
+        paper::plugin,
+        drawing::plugin,
         wife::plugin,
+        npc::plugin,
+        speech::plugin,
         dino::plugin,
         fire::plugin,
+        trigger::plugin,
+        ending::plugin,
+    ));
+    app.add_plugins((
+        difficulty::plugin,
+        stats::plugin,
+        speedrun::plugin,
+        ghost::plugin,
+        history::plugin,
+        journal::plugin,
+        hints::plugin,
+        achievements::plugin,
+        ducks::plugin,
+        item_flight::plugin,
     ));
 }
@@ -9,16 +9,12 @@
 //! - Apply movement based on [`MovementController`] intent and maximum speed.
 //! - Wrap the character within the window.
 //!
-//! Note that the implementation used here is limited for demonstration
-//! purposes. If you want to move the player in a smoother way,
-//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/main/examples/movement/physics_in_fixed_timestep.rs).
+//! Movement is applied on a [fixed timestep](https://github.com/bevyengine/bevy/blob/main/examples/movement/physics_in_fixed_timestep.rs),
+//! so that it doesn't depend on the frame rate and replays come out the same.
 
 use bevy::{prelude::*, window::PrimaryWindow};
 
-use crate::{
-    screens::{Area, Screen},
-    AppSet,
-};
+use crate::screens::{Area, Screen};
 
 use super::player::Player;
 
@@ -27,10 +23,9 @@ pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ActionsFrozen>();
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         (apply_movement, clamp_player_x, change_level)
             .chain()
-            .in_set(AppSet::Update)
             .run_if(in_state(Screen::Gameplay)),
     );
 
@@ -112,20 +107,63 @@ fn clamp_player_x(
     }
 }
 
+/// Why player actions are frozen.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeReason {
+    /// A Yarn dialogue is running.
+    Dialogue,
+    /// The paper is open for writing or reading.
+    Paper,
+    /// The player is being moved by a script.
+    Cutscene,
+    /// A menu is open on top of the game.
+    Pause,
+}
+
+/// A freeze of player actions. It is owned by the entity that caused it,
+/// like the dialogue runner or an open menu, so it can only be lifted for
+/// that entity and freezes of different owners don't collapse into one.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Freeze {
+    pub reason: FreezeReason,
+    pub owner: Entity,
+}
+
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource)]
 pub struct ActionsFrozen {
-    freeze_count: usize,
+    freezes: Vec<Freeze>,
 }
 
 impl ActionsFrozen {
-    pub fn freeze(&mut self) {
-        self.freeze_count += 1;
+    /// Freeze actions for the given reason on behalf of `owner`. Freezing
+    /// again with the same reason and owner has no effect.
+    pub fn freeze(&mut self, reason: FreezeReason, owner: Entity) {
+        let freeze = Freeze { reason, owner };
+        if !self.freezes.contains(&freeze) {
+            self.freezes.push(freeze);
+        }
     }
-    pub fn unfreeze(&mut self) {
-        self.freeze_count -= 1;
+
+    /// Lift the freeze `owner` holds for the given reason, if there is one.
+    pub fn unfreeze(&mut self, reason: FreezeReason, owner: Entity) {
+        self.freezes
+            .retain(|freeze| *freeze != Freeze { reason, owner });
     }
+
     pub fn is_frozen(&self) -> bool {
-        self.freeze_count > 0
+        !self.freezes.is_empty()
+    }
+
+    /// Whether actions are frozen for any reason that isn't ignored.
+    pub fn is_frozen_ignoring(&self, ignored: &[FreezeReason]) -> bool {
+        self.freezes
+            .iter()
+            .any(|freeze| !ignored.contains(&freeze.reason))
+    }
+
+    /// The currently active freezes, oldest first.
+    pub fn freezes(&self) -> &[Freeze] {
+        &self.freezes
     }
 }
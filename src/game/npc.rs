@@ -0,0 +1,206 @@
+//! Scripted behavior for non-player characters.
+//! NPCs move through the same [`MovementController`] as the player, so
+//! animations and movement work the same way for every character.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{audio::SoundEffect, replay::GameRng, screens::Screen, AppSet};
+
+use super::{
+    movement::{ActionsFrozen, FreezeReason, MovementController},
+    player::Player,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<NpcBehavior>();
+    app.add_systems(
+        Update,
+        (
+            tick_npc_timers.in_set(AppSet::TickTimers),
+            (run_routine, bark).in_set(AppSet::RecordInput),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(play_bark_sound);
+}
+
+/// Configures what an NPC does on its own.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NpcBehavior {
+    /// Steps that are executed in order and then repeated.
+    pub routine: Vec<RoutineStep>,
+    /// The NPC stops and turns to the player if they are closer than this.
+    pub notice_distance: f32,
+    /// Short lines of which one is picked at random every `bark_interval`.
+    pub barks: Vec<String>,
+    pub bark_interval: Duration,
+    /// Sound played along with a bark.
+    pub bark_sound: Option<Handle<AudioSource>>,
+    step: usize,
+    step_timer: Timer,
+    bark_timer: Timer,
+}
+
+impl NpcBehavior {
+    pub fn new(routine: Vec<RoutineStep>) -> Self {
+        let step_timer = match routine.first() {
+            Some(RoutineStep::Idle(duration)) => Timer::new(*duration, TimerMode::Once),
+            _ => Timer::default(),
+        };
+        Self {
+            routine,
+            notice_distance: 0.0,
+            barks: Vec::new(),
+            bark_interval: Duration::ZERO,
+            bark_sound: None,
+            step: 0,
+            step_timer,
+            bark_timer: Timer::default(),
+        }
+    }
+
+    pub fn with_notice_distance(mut self, distance: f32) -> Self {
+        self.notice_distance = distance;
+        self
+    }
+
+    pub fn with_barks(
+        mut self,
+        barks: Vec<String>,
+        interval: Duration,
+        sound: Option<Handle<AudioSource>>,
+    ) -> Self {
+        self.barks = barks;
+        self.bark_interval = interval;
+        self.bark_sound = sound;
+        self.bark_timer = Timer::new(interval, TimerMode::Repeating);
+        self
+    }
+
+    fn next_step(&mut self) {
+        self.step = (self.step + 1) % self.routine.len();
+        if let RoutineStep::Idle(duration) = self.routine[self.step] {
+            self.step_timer = Timer::new(duration, TimerMode::Once);
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum RoutineStep {
+    /// Stand still for a while.
+    Idle(Duration),
+    /// Walk to the given x position.
+    WalkTo(f32),
+}
+
+/// Event triggered on an NPC when it says one of its barks.
+#[derive(Event, Debug, Clone)]
+pub struct Bark(pub String);
+
+/// NPCs keep going about their day while the player is reading or writing.
+const NPC_IGNORED_FREEZES: &[FreezeReason] = &[FreezeReason::Paper];
+
+/// How close an NPC has to get to the target of a [`RoutineStep::WalkTo`].
+const ARRIVAL_DISTANCE: f32 = 4.0;
+
+fn tick_npc_timers(
+    time: Res<Time>,
+    actions_frozen: Res<ActionsFrozen>,
+    mut npcs: Query<&mut NpcBehavior>,
+) {
+    if actions_frozen.is_frozen_ignoring(NPC_IGNORED_FREEZES) {
+        return;
+    }
+    for mut npc in &mut npcs {
+        npc.step_timer.tick(time.delta());
+        npc.bark_timer.tick(time.delta());
+    }
+}
+
+fn run_routine(
+    actions_frozen: Res<ActionsFrozen>,
+    player: Query<&Transform, With<Player>>,
+    mut npcs: Query<
+        (
+            &Transform,
+            &mut NpcBehavior,
+            &mut MovementController,
+            &mut Sprite,
+        ),
+        Without<Player>,
+    >,
+) {
+    let player = player.get_single().ok();
+    for (transform, mut npc, mut controller, mut sprite) in &mut npcs {
+        controller.intent = Vec2::ZERO;
+        if actions_frozen.is_frozen_ignoring(NPC_IGNORED_FREEZES) || npc.routine.is_empty() {
+            continue;
+        }
+
+        let x = transform.translation.x;
+        if let Some(player) = player {
+            let dx = player.translation.x - x;
+            if dx.abs() < npc.notice_distance {
+                sprite.flip_x = dx < 0.0;
+                continue;
+            }
+        }
+
+        match npc.routine[npc.step] {
+            RoutineStep::Idle(_) => {
+                if npc.step_timer.finished() {
+                    npc.next_step();
+                }
+            }
+            RoutineStep::WalkTo(target) => {
+                let dx = target - x;
+                if dx.abs() < ARRIVAL_DISTANCE {
+                    npc.next_step();
+                } else {
+                    controller.intent = Vec2::new(dx.signum(), 0.0);
+                }
+            }
+        }
+    }
+}
+
+fn bark(
+    mut commands: Commands,
+    actions_frozen: Res<ActionsFrozen>,
+    npcs: Query<(Entity, &NpcBehavior)>,
+    mut rng: ResMut<GameRng>,
+) {
+    if actions_frozen.is_frozen_ignoring(NPC_IGNORED_FREEZES) {
+        return;
+    }
+    for (entity, npc) in &npcs {
+        if !npc.bark_timer.just_finished() {
+            continue;
+        }
+        if let Some(line) = npc.barks.choose(&mut rng.0) {
+            commands.trigger_targets(Bark(line.clone()), entity);
+        }
+    }
+}
+
+fn play_bark_sound(trigger: Trigger<Bark>, mut commands: Commands, npcs: Query<&NpcBehavior>) {
+    let Some(sound) = npcs
+        .get(trigger.entity())
+        .ok()
+        .and_then(|npc| npc.bark_sound.clone())
+    else {
+        return;
+    };
+    commands.spawn((
+        AudioBundle {
+            source: sound,
+            settings: PlaybackSettings::DESPAWN,
+        },
+        SoundEffect,
+        Name::from("Bark sound"),
+    ));
+}
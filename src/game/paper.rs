@@ -0,0 +1,329 @@
+//! The big paper the caveman can write and draw on once he learned how to.
+
+use bevy::{color::palettes::css::BLACK, input::common_conditions::input_just_pressed, prelude::*};
+use bevy_yarnspinner::prelude::DialogueRunner;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::SoundEffect,
+    localization::Localization,
+    persistence::{PersistResource, Persistent},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+use super::{
+    drawing::DrawingCanvas,
+    inventory::{Inventory, Item},
+    level::Level,
+    movement::{ActionsFrozen, FreezeReason},
+    player::PlayerAssets,
+    recipe::{use_node, Recipe, Station},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<WrittenPaper>();
+    app.init_resource::<WrittenPaper>();
+    app.register_type::<PlayerName>();
+    app.persist_resource::<PlayerName>();
+
+    app.add_systems(
+        Update,
+        (
+            request_close_paper.run_if(input_just_pressed(KeyCode::Escape)),
+            toggle_paper_mode.run_if(input_just_pressed(KeyCode::Tab)),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(open_paper);
+    app.observe(confirm_paper);
+    app.observe(close_paper);
+
+    app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
+        commands.insert_resource(WrittenPaper::default())
+    });
+}
+
+/// What the caveman put on the paper, kept along with the
+/// [`Item::WRITTEN_PAPER`].
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct WrittenPaper {
+    pub text: String,
+    pub drawing: Option<Handle<Image>>,
+}
+
+impl WrittenPaper {
+    /// What Yarn sees as `$paper_text`, see [`normalize_text`].
+    pub fn normalized_text(&self) -> String {
+        normalize_text(&self.text)
+    }
+}
+
+/// The text in upper case with single spaces between words, so that Yarn
+/// can compare it regardless of how it was typed.
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+/// The name the player entered in the settings. The wife recognizes it on
+/// the paper, Yarn sees it as `$player_name`.
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct PlayerName {
+    pub name: String,
+}
+
+impl Persistent for PlayerName {
+    const KEY: &'static str = "player_name";
+}
+
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenPaper {
+    /// Open the blank paper to write or draw on it.
+    Write,
+    /// Look at the [`WrittenPaper`] again.
+    Read,
+}
+
+#[derive(Event, Debug)]
+struct ClosePaper;
+
+#[derive(Reflect, Component, Debug)]
+struct Paper {
+    mode: OpenPaper,
+    editable: bool,
+}
+
+#[derive(Reflect, Component, Debug)]
+struct PaperText;
+
+const PAPER_LINE_WIDTH: usize = 6;
+const PAPER_MAX_LINES: usize = 4;
+/// Size of `paper_big.png`, which the drawing canvas covers.
+const PAPER_SIZE: UVec2 = UVec2::splat(100);
+
+fn open_paper(
+    trigger: Trigger<OpenPaper>,
+    mut commands: Commands,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+    mut images: ResMut<Assets<Image>>,
+    dialogue_runner: Query<&DialogueRunner>,
+    inventory: Res<Inventory>,
+    written_paper: Res<WrittenPaper>,
+    player_assets: Res<PlayerAssets>,
+    localization: Res<Localization>,
+) {
+    if actions_frozen.is_frozen() {
+        return;
+    }
+    let dialogue_runner = dialogue_runner
+        .get_single()
+        .expect("only one dialogue runner");
+    let editable = *trigger.event() == OpenPaper::Write
+        && Recipe::at(Station::Paper).any(|recipe| {
+            inventory.contains(recipe.input)
+                && recipe.is_unlocked_in(dialogue_runner.variable_storage())
+        });
+
+    let paper = commands
+        .spawn((
+            Name::new("Paper"),
+            Paper {
+                mode: *trigger.event(),
+                editable,
+            },
+            SpriteBundle {
+                texture: player_assets.paper_big.clone(),
+                transform: Transform::from_scale(Vec2::splat(7.0).extend(1.0))
+                    .with_translation(Vec3::new(0.0, 0.0, 70.0)),
+                ..Default::default()
+            },
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            let transform = Transform::from_translation(Vec3::new(0.0, 0.0, 0.5));
+            match trigger.event() {
+                OpenPaper::Write => {
+                    let canvas = DrawingCanvas::new(&mut images, PAPER_SIZE);
+                    children.spawn((
+                        Name::new("Paper Drawing"),
+                        SpriteBundle {
+                            texture: canvas.image.clone(),
+                            transform,
+                            ..default()
+                        },
+                        canvas,
+                    ));
+                }
+                OpenPaper::Read => {
+                    if let Some(drawing) = &written_paper.drawing {
+                        children.spawn((
+                            Name::new("Paper Drawing"),
+                            SpriteBundle {
+                                texture: drawing.clone(),
+                                transform,
+                                ..default()
+                            },
+                        ));
+                    }
+                }
+            }
+        })
+        .id();
+    actions_frozen.freeze(FreezeReason::Paper, paper);
+
+    let mut input = TextInput::new(PAPER_LINE_WIDTH, PAPER_MAX_LINES);
+    input.focused = editable;
+    if *trigger.event() == OpenPaper::Read {
+        input.set_value(written_paper.text.clone());
+    }
+    commands
+        .spawn((
+            Name::new("Paper Text"),
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: player_assets.animal_font.clone(),
+                        font_size: 80.0,
+                        color: BLACK.into(),
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 80.0)),
+                ..default()
+            },
+            input,
+            PaperText,
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            if !editable {
+                return;
+            }
+            children.spawn((
+                Name::new("Paper Hint"),
+                Text2dBundle {
+                    text: Text::from_section(
+                        localization.get("Tab: write/draw   Z: undo   C: clear   [ ]: brush"),
+                        TextStyle {
+                            font: UI_FONT,
+                            font_size: 20.0,
+                            color: BLACK.into(),
+                        },
+                    ),
+                    transform: Transform::from_translation(Vec3::new(0.0, -300.0, 0.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Switch between typing and drawing on an editable paper.
+fn toggle_paper_mode(
+    paper: Query<&Paper>,
+    mut text: Query<&mut TextInput, With<PaperText>>,
+    mut canvas: Query<&mut DrawingCanvas>,
+) {
+    if !paper.iter().any(|paper| paper.editable) {
+        return;
+    }
+    for mut input in &mut text {
+        input.focused = !input.focused;
+    }
+    for mut canvas in &mut canvas {
+        canvas.active = !canvas.active;
+    }
+}
+
+fn request_close_paper(mut commands: Commands) {
+    commands.trigger(ClosePaper);
+}
+
+fn confirm_paper(
+    trigger: Trigger<OnTextConfirm>,
+    mut commands: Commands,
+    paper_text: Query<(), With<PaperText>>,
+) {
+    if paper_text.contains(trigger.entity()) {
+        commands.trigger(ClosePaper);
+    }
+}
+
+fn close_paper(
+    _: Trigger<ClosePaper>,
+    mut commands: Commands,
+    paper: Query<(Entity, &Paper)>,
+    paper_text: Query<(Entity, &TextInput), With<PaperText>>,
+    canvas: Query<&DrawingCanvas>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
+    mut inventory: ResMut<Inventory>,
+    mut level: ResMut<Level>,
+    mut written_paper: ResMut<WrittenPaper>,
+    player_assets: Res<PlayerAssets>,
+) {
+    let Ok((entity, paper)) = paper.get_single() else {
+        return;
+    };
+    commands.entity(entity).despawn_recursive();
+    actions_frozen.unfreeze(FreezeReason::Paper, entity);
+
+    let mut text = String::new();
+    for (entity, input) in &paper_text {
+        text = input.value().trim().to_string();
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let (runner, mut dialogue_runner) = dialogue_runner
+        .get_single_mut()
+        .expect("only one dialogue runner");
+    if paper.mode == OpenPaper::Read {
+        if let Some(node) = use_node(&Item::new(Item::WRITTEN_PAPER)) {
+            dialogue_runner.start_node(node);
+            actions_frozen.freeze(FreezeReason::Dialogue, runner);
+        }
+        return;
+    }
+
+    let drawing = canvas.iter().find(|canvas| !canvas.is_empty());
+    if !paper.editable || text.is_empty() && drawing.is_none() {
+        return;
+    }
+    written_paper.text = text;
+    written_paper.drawing = drawing.map(|canvas| canvas.image.clone());
+
+    let Some(recipe) = Recipe::at(Station::Paper).find(|recipe| inventory.contains(recipe.input))
+    else {
+        return;
+    };
+    let vars = dialogue_runner.variable_storage_mut();
+    recipe.make(&mut inventory, &mut level, vars);
+    // Let the dialogue react to what was written.
+    vars.set(
+        "$paper_text".to_string(),
+        written_paper.normalized_text().into(),
+    )
+    .unwrap();
+    vars.set(
+        "$paper_drawn".to_string(),
+        written_paper.drawing.is_some().into(),
+    )
+    .unwrap();
+
+    commands.spawn((
+        AudioBundle {
+            source: player_assets.item_pickup.clone(),
+            settings: PlaybackSettings::DESPAWN,
+        },
+        SoundEffect,
+        Name::from("Convert sound"),
+    ));
+    if let Some(node) = recipe.node {
+        dialogue_runner.start_node(node);
+        actions_frozen.freeze(FreezeReason::Dialogue, runner);
+    }
+}
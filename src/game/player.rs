@@ -4,13 +4,11 @@
 
 use std::time::Duration;
 
-use bevy::{
-    prelude::*,
-    render::texture::{ImageLoaderSettings, ImageSampler},
-};
+use bevy::prelude::*;
 
 use crate::{
-    asset_tracking::LoadResource,
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
     game::{animation::Animation, movement::MovementController},
     screens::Screen,
     AppSet,
@@ -18,12 +16,12 @@ use crate::{
 
 use super::{
     animation::{AnimationData, AnimationState},
-    movement::ActionsFrozen,
+    movement::{ActionsFrozen, FreezeReason},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Player>();
-    app.load_resource::<PlayerAssets>();
+    app.load_collection::<PlayerAssets>();
     app.add_systems(OnEnter(Screen::Gameplay), spawn_player);
 
     // Record directional input as movement controls.
@@ -44,27 +42,8 @@ fn spawn_player(
     player_assets: Res<PlayerAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
-    let layout = TextureAtlasLayout::from_grid(
-        UVec2::new(16, 23),
-        4,
-        1,
-        Some(UVec2::splat(2)),
-        Some(UVec2::splat(1)),
-    );
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let idle = AnimationData {
-        frames: 2,
-        interval: Duration::from_millis(200),
-        state: AnimationState::Idling,
-        atlas_index: 0,
-    };
-    let walk = AnimationData {
-        frames: 2,
-        interval: Duration::from_millis(100),
-        state: AnimationState::Walking,
-        atlas_index: 2,
-    };
-    let player_animation = Animation::new(vec![idle, walk]);
+    let texture_atlas_layout = texture_atlas_layouts.add(caveman_atlas_layout());
+    let player_animation = caveman_animation();
 
     commands.spawn((
         Name::new("Player"),
@@ -99,6 +78,33 @@ fn spawn_player(
     ));
 }
 
+/// The layout of `caveman.png`.
+pub(super) fn caveman_atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(
+        UVec2::new(16, 23),
+        4,
+        1,
+        Some(UVec2::splat(2)),
+        Some(UVec2::splat(1)),
+    )
+}
+
+pub(super) fn caveman_animation() -> Animation {
+    let idle = AnimationData {
+        frames: 2,
+        interval: Duration::from_millis(200),
+        state: AnimationState::Idling,
+        atlas_index: 0,
+    };
+    let walk = AnimationData {
+        frames: 2,
+        interval: Duration::from_millis(100),
+        state: AnimationState::Walking,
+        atlas_index: 2,
+    };
+    Animation::new(vec![idle, walk])
+}
+
 fn record_player_directional_input(
     input: Res<ButtonInput<KeyCode>>,
     mut controllers: Query<&mut MovementController, With<Player>>,
@@ -130,80 +136,40 @@ fn record_player_directional_input(
     }
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct PlayerAssets {
-    #[dependency]
     pub caveman: Handle<Image>,
-    #[dependency]
     pub healthbar: Handle<Image>,
-    #[dependency]
     pub paper_big: Handle<Image>,
 
-    #[dependency]
     pub item_pickup: Handle<AudioSource>,
-    #[dependency]
     pub vine_boom: Handle<AudioSource>,
-    #[dependency]
     pub uh_oh: Handle<AudioSource>,
-    #[dependency]
     pub trophy_wife: Handle<AudioSource>,
-    #[dependency]
     pub wife_hm: Handle<AudioSource>,
-    #[dependency]
     pub run_outside: Handle<AudioSource>,
-    #[dependency]
     pub run_cave: Handle<AudioSource>,
 
-    #[dependency]
     pub animal_font: Handle<Font>,
 }
 
-impl PlayerAssets {
-    pub const PATH_CAVEMAN: &'static str = "images/caveman.png";
-    pub const PATH_HEALTHBAR: &'static str = "images/health_bar.png";
-    pub const PATH_PAPER_BIG: &'static str = "images/paper_big.png";
-    pub const PATH_ITEM_PICKUP: &'static str = "audio/sound_effects/item_pickup.ogg";
-    pub const PATH_VINE_BOOM: &'static str = "audio/sound_effects/vine_boom.ogg";
-    pub const PATH_UH_OH: &'static str = "audio/sound_effects/uh_oh.ogg";
-    pub const PATH_TROPHY_WIFE: &'static str = "audio/sound_effects/trophy_wife.ogg";
-    pub const PATH_WIFE_HM: &'static str = "audio/sound_effects/wife_hm.ogg";
-    pub const PATH_RUN_OUTSIDE: &'static str = "audio/sound_effects/run_outside.ogg";
-    pub const PATH_RUN_CAVE: &'static str = "audio/sound_effects/run_cave.ogg";
-    pub const PATH_ANIMAL_FONT: &'static str = "fonts/Animal-Alphabet-Regular.ttf";
-}
-
-impl FromWorld for PlayerAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            caveman: assets.load_with_settings(
-                PlayerAssets::PATH_CAVEMAN,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            healthbar: assets.load_with_settings(
-                PlayerAssets::PATH_HEALTHBAR,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            paper_big: assets.load_with_settings(
-                PlayerAssets::PATH_PAPER_BIG,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            item_pickup: assets.load(PlayerAssets::PATH_ITEM_PICKUP),
-            vine_boom: assets.load(PlayerAssets::PATH_VINE_BOOM),
-            uh_oh: assets.load(PlayerAssets::PATH_UH_OH),
-            trophy_wife: assets.load(PlayerAssets::PATH_TROPHY_WIFE),
-            wife_hm: assets.load(PlayerAssets::PATH_WIFE_HM),
-
-            run_outside: assets.load(PlayerAssets::PATH_RUN_OUTSIDE),
-            run_cave: assets.load(PlayerAssets::PATH_RUN_CAVE),
-            animal_font: assets.load(PlayerAssets::PATH_ANIMAL_FONT),
-        }
+impl FromCollection for PlayerAssets {
+    const COLLECTION: &'static str = "player";
+
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            caveman: collection.image("caveman")?,
+            healthbar: collection.image("healthbar")?,
+            paper_big: collection.image("paper_big")?,
+            item_pickup: collection.audio("item_pickup")?,
+            vine_boom: collection.audio("vine_boom")?,
+            uh_oh: collection.audio("uh_oh")?,
+            trophy_wife: collection.audio("trophy_wife")?,
+            wife_hm: collection.audio("wife_hm")?,
+            run_outside: collection.audio("run_outside")?,
+            run_cave: collection.audio("run_cave")?,
+            animal_font: collection.font("animal")?,
+        })
     }
 }
 
@@ -215,6 +181,7 @@ pub struct AutoRunner {
 
 fn auto_run(
     mut commands: Commands,
+    mut actions_frozen: ResMut<ActionsFrozen>,
     mut controllers: Query<(
         Entity,
         &Transform,
@@ -230,6 +197,7 @@ fn auto_run(
         {
             controller.intent = Vec2::ZERO;
             commands.entity(entity).remove::<AutoRunner>();
+            actions_frozen.unfreeze(FreezeReason::Cutscene, entity);
             sprite.flip_x = false;
 
             continue;
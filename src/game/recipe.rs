@@ -0,0 +1,137 @@
+//! Items that the game turns into other items, rather than the dialogue, and
+//! the nodes the game starts when items are handled. The game and the puzzle
+//! checker both go by these definitions.
+
+use bevy_yarnspinner::prelude::{VariableStorage, YarnValue};
+
+use super::{
+    inventory::{Inventory, Item, ItemDefinitions},
+    level::Level,
+};
+
+/// Where a recipe is made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Station {
+    /// Pressing E at the fire.
+    Fire,
+    /// Writing or drawing on the paper and closing it.
+    Paper,
+}
+
+/// Where the made item ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    Inventory,
+    Level,
+}
+
+#[derive(Debug)]
+pub struct Recipe {
+    pub station: Station,
+    /// The id of the item taken from the inventory.
+    pub input: &'static str,
+    /// The id of the item made.
+    pub output: &'static str,
+    pub output_place: Place,
+    /// A Yarn variable that has to be true for the recipe to work.
+    pub requires: Option<&'static str>,
+    /// The node started once the recipe is made.
+    pub node: Option<&'static str>,
+}
+
+pub const RECIPES: [Recipe; 2] = [
+    Recipe {
+        station: Station::Fire,
+        input: Item::BANANA,
+        output: Item::BURNT_BANANA,
+        output_place: Place::Level,
+        requires: None,
+        node: Some("DroppedBanana"),
+    },
+    Recipe {
+        station: Station::Paper,
+        input: Item::PAPER,
+        output: Item::WRITTEN_PAPER,
+        output_place: Place::Inventory,
+        // The caveman can't write without knowing what to write with.
+        requires: Some("$learned_pen"),
+        node: None,
+    },
+];
+
+/// The node started by pressing E at the fire without anything to burn, as
+/// long as nothing burnt lies in the level.
+pub const FIRE_NODE: &str = "Fire";
+
+impl Recipe {
+    pub fn at(station: Station) -> impl Iterator<Item = &'static Recipe> {
+        RECIPES
+            .iter()
+            .filter(move |recipe| recipe.station == station)
+    }
+
+    /// Whether the required variable is set, as told by `is_true`.
+    pub fn is_unlocked(&self, is_true: impl Fn(&str) -> bool) -> bool {
+        self.requires.is_none_or(is_true)
+    }
+
+    /// Whether the required variable is set in the dialogue.
+    pub fn is_unlocked_in(&self, variables: &dyn VariableStorage) -> bool {
+        self.is_unlocked(|name| variables.get(name).ok() == Some(YarnValue::Boolean(true)))
+    }
+
+    /// Take the input from the inventory and put the output where it goes,
+    /// keeping the `$_has_` variables of the dialogue in sync.
+    pub fn make(
+        &self,
+        inventory: &mut Inventory,
+        level: &mut Level,
+        variables: &mut dyn VariableStorage,
+    ) {
+        if let Some(index) = inventory.items.iter().position(|x| x.id() == self.input) {
+            inventory.items.remove(index);
+        }
+        variables
+            .set(format!("$_has_{}", self.input), false.into())
+            .unwrap();
+        match self.output_place {
+            Place::Inventory => {
+                inventory.items.push(Item::new(self.output));
+                variables
+                    .set(format!("$_has_{}", self.output), true.into())
+                    .unwrap();
+            }
+            Place::Level => level.items.push(Item::new(self.output)),
+        }
+    }
+}
+
+/// The node started when the item is picked up.
+pub fn pickup_node(item: &Item) -> Option<&'static str> {
+    match item.id() {
+        Item::PAPER => Some("CollectedPaper"),
+        _ => None,
+    }
+}
+
+/// The node started by using the item in the inventory. The written paper is
+/// read first, and the paper is written on instead.
+pub fn use_node(item: &Item) -> Option<String> {
+    Recipe::at(Station::Paper)
+        .all(|recipe| recipe.input != item.id())
+        .then(|| item.to_string())
+}
+
+/// Every node that is started by the definitions in this module for the
+/// given items.
+pub fn started_nodes(items: &ItemDefinitions) -> Vec<String> {
+    let mut nodes = vec![FIRE_NODE.to_string()];
+    nodes.extend(
+        RECIPES
+            .iter()
+            .filter_map(|recipe| recipe.node.map(String::from)),
+    );
+    nodes.extend(items.ids().filter_map(pickup_node).map(String::from));
+    nodes.extend(items.ids().filter_map(use_node));
+    nodes
+}
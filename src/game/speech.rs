@@ -0,0 +1,191 @@
+//! Speech bubbles that show short lines above a character's head without
+//! blocking gameplay like the dialogue runner does.
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    text::{Text2dBounds, TextLayoutInfo},
+};
+
+use crate::{localization::Localization, screens::Screen, theme::prelude::*, AppSet};
+
+use super::npc::Bark;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SpeechBubble>();
+    app.add_systems(
+        Update,
+        (
+            tick_speech_bubbles.in_set(AppSet::TickTimers),
+            (
+                despawn_speech_bubbles,
+                follow_speaker,
+                fit_bubble_background,
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(spawn_speech_bubble);
+    app.observe(say_bark);
+}
+
+/// Event triggered on a character to show a line above their head. The text
+/// is written in English and translated when it is shown.
+#[derive(Event, Debug, Clone)]
+pub struct Say {
+    pub text: String,
+    /// How long the bubble stays before it disappears on its own.
+    pub duration: Duration,
+}
+
+impl Say {
+    /// Create a line that stays long enough to be read.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let duration = Duration::from_millis(1500 + 60 * text.chars().count() as u64);
+        Self { text, duration }
+    }
+}
+
+/// Find the character with the given [`Name`] to [`Say`] something.
+pub fn find_speaker<'a>(
+    names: impl IntoIterator<Item = (Entity, &'a Name)>,
+    speaker: &str,
+) -> Option<Entity> {
+    names
+        .into_iter()
+        .find(|(_, name)| name.as_str() == speaker)
+        .map(|(entity, _)| entity)
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SpeechBubble {
+    speaker: Entity,
+    timer: Timer,
+}
+
+#[derive(Component, Debug)]
+struct SpeechBubbleText;
+
+/// Height above the speaker's center at which bubbles are shown.
+const BUBBLE_OFFSET: f32 = 130.0;
+const BUBBLE_PADDING: Vec2 = Vec2::new(16.0, 10.0);
+const BUBBLE_MAX_WIDTH: f32 = 300.0;
+
+fn spawn_speech_bubble(
+    trigger: Trigger<Say>,
+    mut commands: Commands,
+    bubbles: Query<(Entity, &SpeechBubble)>,
+    speakers: Query<&Transform>,
+    localization: Res<Localization>,
+) {
+    let speaker = trigger.entity();
+    let Ok(speaker_transform) = speakers.get(speaker) else {
+        return;
+    };
+    // Only keep the newest line of every speaker.
+    for (entity, bubble) in &bubbles {
+        if bubble.speaker == speaker {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let say = trigger.event();
+    commands
+        .spawn((
+            Name::new("Speech Bubble"),
+            SpeechBubble {
+                speaker,
+                timer: Timer::new(say.duration, TimerMode::Once),
+            },
+            SpriteBundle {
+                sprite: Sprite {
+                    color: ui_palette::BUBBLE_BACKGROUND,
+                    custom_size: Some(Vec2::ZERO),
+                    ..default()
+                },
+                transform: Transform::from_translation(bubble_translation(speaker_transform)),
+                ..default()
+            },
+            StateScoped(Screen::Gameplay),
+        ))
+        .with_children(|children| {
+            children.spawn((
+                Name::new("Speech Bubble Text"),
+                SpeechBubbleText,
+                Text2dBundle {
+                    text: Text::from_section(
+                        localization.get(&say.text),
+                        TextStyle {
+                            font: UI_FONT,
+                            font_size: 24.0,
+                            color: ui_palette::BUBBLE_TEXT,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    text_2d_bounds: Text2dBounds {
+                        size: Vec2::new(BUBBLE_MAX_WIDTH, f32::INFINITY),
+                    },
+                    transform: Transform::from_translation(Vec3::Z),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn bubble_translation(speaker: &Transform) -> Vec3 {
+    Vec3::new(
+        speaker.translation.x,
+        speaker.translation.y + BUBBLE_OFFSET,
+        65.0,
+    )
+}
+
+fn say_bark(trigger: Trigger<Bark>, mut commands: Commands) {
+    commands.trigger_targets(Say::new(trigger.event().0.clone()), trigger.entity());
+}
+
+fn tick_speech_bubbles(time: Res<Time>, mut bubbles: Query<&mut SpeechBubble>) {
+    for mut bubble in &mut bubbles {
+        bubble.timer.tick(time.delta());
+    }
+}
+
+fn despawn_speech_bubbles(
+    mut commands: Commands,
+    bubbles: Query<(Entity, &SpeechBubble)>,
+    speakers: Query<(), With<Transform>>,
+) {
+    for (entity, bubble) in &bubbles {
+        if bubble.timer.finished() || !speakers.contains(bubble.speaker) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn follow_speaker(
+    mut bubbles: Query<(&SpeechBubble, &mut Transform)>,
+    speakers: Query<&Transform, Without<SpeechBubble>>,
+) {
+    for (bubble, mut transform) in &mut bubbles {
+        if let Ok(speaker) = speakers.get(bubble.speaker) {
+            transform.translation = bubble_translation(speaker);
+        }
+    }
+}
+
+/// Size the bubble background to the text once it has been laid out.
+fn fit_bubble_background(
+    texts: Query<(&Parent, &TextLayoutInfo), (With<SpeechBubbleText>, Changed<TextLayoutInfo>)>,
+    mut bubbles: Query<&mut Sprite, With<SpeechBubble>>,
+) {
+    for (parent, layout) in &texts {
+        if let Ok(mut sprite) = bubbles.get_mut(parent.get()) {
+            sprite.custom_size = Some(layout.logical_size + 2.0 * BUBBLE_PADDING);
+        }
+    }
+}
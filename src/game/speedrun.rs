@@ -0,0 +1,341 @@
+//! An optional speedrun timer with automatic splits.
+//! Finished runs are compared to the best segments so far and exported as a
+//! LiveSplit `.lss` file.
+
+use std::{fmt::Write, time::Duration};
+
+use bevy::prelude::*;
+use bevy_yarnspinner::events::NodeCompleteEvent;
+use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::{self, PersistResource, Persistent},
+    screens::Screen,
+    theme::prelude::*,
+    AppSet,
+};
+
+use super::{
+    ending::ReachEnding,
+    inventory::{Inventory, Item},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SpeedrunTimer>();
+    app.register_type::<SpeedrunSettings>();
+    app.persist_resource::<SpeedrunSettings>();
+    app.persist_resource::<SpeedrunRecords>();
+    app.init_resource::<SpeedrunTimer>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_timer, spawn_timer_display)
+            .chain()
+            .run_if(timer_enabled),
+    );
+    app.add_systems(
+        Update,
+        (
+            tick_timer.in_set(AppSet::TickTimers),
+            start_timer.in_set(AppSet::RecordInput),
+            (
+                split_on_milestones.run_if(resource_changed::<Inventory>),
+                update_timer_display,
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Gameplay).and_then(timer_enabled)),
+    );
+    app.observe(finish_run);
+}
+
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct SpeedrunSettings {
+    /// Whether the timer is shown and runs are recorded.
+    pub enabled: bool,
+}
+
+impl Persistent for SpeedrunSettings {
+    const KEY: &'static str = "speedrun_settings";
+}
+
+/// The points at which the timer splits, in order.
+#[derive(Reflect, Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum Split {
+    #[display("First Item")]
+    FirstItem,
+    #[display("Papyrus Strips")]
+    PapyrusStrips,
+    #[display("Woven Papyrus")]
+    WovenPapyrus,
+    Paper,
+    #[display("Written Paper")]
+    WrittenPaper,
+    End,
+}
+
+impl Split {
+    pub const ALL: [Split; 6] = [
+        Split::FirstItem,
+        Split::PapyrusStrips,
+        Split::WovenPapyrus,
+        Split::Paper,
+        Split::WrittenPaper,
+        Split::End,
+    ];
+
+    /// Whether the milestone of this split was reached with these items.
+    fn reached(self, inventory: &Inventory) -> bool {
+        let item = match self {
+            Split::FirstItem => return !inventory.items.is_empty(),
+            Split::PapyrusStrips => Item::PAPYRUS_STRIPS,
+            Split::WovenPapyrus => Item::WOVEN_PAPYRUS,
+            Split::Paper => Item::PAPER,
+            Split::WrittenPaper => Item::WRITTEN_PAPER,
+            Split::End => return false,
+        };
+        inventory.contains(item)
+    }
+}
+
+/// The timer of the current run.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct SpeedrunTimer {
+    pub running: bool,
+    pub elapsed: Duration,
+    /// Time at which each split of [`Split::ALL`] was reached so far.
+    pub splits: Vec<Duration>,
+}
+
+impl SpeedrunTimer {
+    fn next_split(&self) -> Option<Split> {
+        Split::ALL.get(self.splits.len()).copied()
+    }
+
+    fn split(&mut self) {
+        self.splits.push(self.elapsed);
+    }
+
+    /// How long each split took on its own.
+    fn segments(&self) -> Vec<Duration> {
+        segments(&self.splits)
+    }
+}
+
+fn segments(splits: &[Duration]) -> Vec<Duration> {
+    let mut previous = Duration::ZERO;
+    splits
+        .iter()
+        .map(|split| {
+            let segment = *split - previous;
+            previous = *split;
+            segment
+        })
+        .collect()
+}
+
+/// Best times over all finished runs.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct SpeedrunRecords {
+    pub attempts: u32,
+    /// Split times of the fastest finished run.
+    pub personal_best: Option<Vec<Duration>>,
+    /// Fastest time of each segment in any run.
+    pub best_segments: Vec<Duration>,
+}
+
+impl Persistent for SpeedrunRecords {
+    const KEY: &'static str = "speedrun_records";
+}
+
+#[derive(Component, Debug)]
+struct TimerDisplay;
+
+const LSS_FILE: &str = "thanks_wife.lss";
+
+fn timer_enabled(settings: Res<SpeedrunSettings>) -> bool {
+    settings.enabled
+}
+
+fn reset_timer(mut commands: Commands, mut records: ResMut<SpeedrunRecords>) {
+    commands.insert_resource(SpeedrunTimer::default());
+    records.attempts += 1;
+}
+
+fn spawn_timer_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Speedrun Timer"),
+        TimerDisplay,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: UI_FONT,
+                font_size: 28.0,
+                color: ui_palette::LABEL_TEXT,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn tick_timer(time: Res<Time>, mut timer: ResMut<SpeedrunTimer>) {
+    if timer.running {
+        timer.elapsed += time.delta();
+    }
+}
+
+/// The run starts once the intro is over.
+fn start_timer(mut events: EventReader<NodeCompleteEvent>, mut timer: ResMut<SpeedrunTimer>) {
+    for event in events.read() {
+        if event.node_name == "Intro" && timer.splits.is_empty() {
+            timer.running = true;
+        }
+    }
+}
+
+fn split_on_milestones(inventory: Res<Inventory>, mut timer: ResMut<SpeedrunTimer>) {
+    if !timer.running {
+        return;
+    }
+    while let Some(split) = timer.next_split() {
+        if !split.reached(&inventory) {
+            break;
+        }
+        timer.split();
+    }
+}
+
+fn finish_run(
+    _: Trigger<ReachEnding>,
+    settings: Res<SpeedrunSettings>,
+    mut timer: ResMut<SpeedrunTimer>,
+    mut records: ResMut<SpeedrunRecords>,
+) {
+    if !settings.enabled || !timer.running {
+        return;
+    }
+    timer.running = false;
+    // Milestones can be skipped by endings that don't need them.
+    if timer.next_split() != Some(Split::End) {
+        return;
+    }
+    timer.split();
+
+    let segments = timer.segments();
+    records.best_segments.resize(segments.len(), Duration::MAX);
+    for (best, segment) in records.best_segments.iter_mut().zip(&segments) {
+        *best = (*best).min(*segment);
+    }
+    let is_personal_best = records
+        .personal_best
+        .as_ref()
+        .is_none_or(|best| best.last() > timer.splits.last());
+    if is_personal_best {
+        records.personal_best = Some(timer.splits.clone());
+    }
+    persistence::write_file(LSS_FILE, &livesplit_run(&records));
+}
+
+fn update_timer_display(
+    timer: Res<SpeedrunTimer>,
+    records: Res<SpeedrunRecords>,
+    mut display: Query<&mut Text, With<TimerDisplay>>,
+) {
+    let mut text = format_time(timer.elapsed);
+    let segments = timer.segments();
+    if let (Some(segment), Some(split)) = (
+        segments.last(),
+        timer.splits.len().checked_sub(1).map(|i| Split::ALL[i]),
+    ) {
+        text.push_str(&format!("\n{split}"));
+        if let Some(best) = records.best_segments.get(segments.len() - 1) {
+            if segment < best {
+                text.push_str(&format!(" -{}", format_time(*best - *segment)));
+            } else {
+                text.push_str(&format!(" +{}", format_time(*segment - *best)));
+            }
+        }
+    }
+    for mut display in &mut display {
+        display.sections[0].value.clone_from(&text);
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!(
+        "{}:{:02}.{}",
+        seconds / 60,
+        seconds % 60,
+        time.subsec_millis() / 100
+    )
+}
+
+/// A time as LiveSplit writes it, e.g. `00:01:23.4560000`.
+fn livesplit_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        time.subsec_nanos() / 100
+    )
+}
+
+/// Write the records as a LiveSplit splits file.
+fn livesplit_run(records: &SpeedrunRecords) -> String {
+    let mut lss = String::new();
+    writeln!(lss, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(lss, r#"<Run version="1.7.0">"#).unwrap();
+    writeln!(lss, "  <GameIcon />").unwrap();
+    writeln!(lss, "  <GameName>Thanks Wife</GameName>").unwrap();
+    writeln!(lss, "  <CategoryName>Any%</CategoryName>").unwrap();
+    writeln!(lss, "  <Offset>00:00:00</Offset>").unwrap();
+    writeln!(lss, "  <AttemptCount>{}</AttemptCount>", records.attempts).unwrap();
+    writeln!(lss, "  <AttemptHistory />").unwrap();
+    writeln!(lss, "  <Segments>").unwrap();
+    for (index, split) in Split::ALL.iter().enumerate() {
+        writeln!(lss, "    <Segment>").unwrap();
+        writeln!(lss, "      <Name>{split}</Name>").unwrap();
+        writeln!(lss, "      <Icon />").unwrap();
+        writeln!(lss, "      <SplitTimes>").unwrap();
+        writeln!(lss, r#"        <SplitTime name="Personal Best">"#).unwrap();
+        if let Some(time) = records.personal_best.as_ref().and_then(|pb| pb.get(index)) {
+            writeln!(
+                lss,
+                "          <RealTime>{}</RealTime>",
+                livesplit_time(*time)
+            )
+            .unwrap();
+        }
+        writeln!(lss, "        </SplitTime>").unwrap();
+        writeln!(lss, "      </SplitTimes>").unwrap();
+        writeln!(lss, "      <BestSegmentTime>").unwrap();
+        if let Some(time) = records.best_segments.get(index) {
+            writeln!(
+                lss,
+                "        <RealTime>{}</RealTime>",
+                livesplit_time(*time)
+            )
+            .unwrap();
+        }
+        writeln!(lss, "      </BestSegmentTime>").unwrap();
+        writeln!(lss, "      <SegmentHistory />").unwrap();
+        writeln!(lss, "    </Segment>").unwrap();
+    }
+    writeln!(lss, "  </Segments>").unwrap();
+    writeln!(lss, "  <AutoSplitterSettings />").unwrap();
+    writeln!(lss, "</Run>").unwrap();
+    lss
+}
@@ -0,0 +1,120 @@
+//! Statistics about the current run, shown on the end screen.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_yarnspinner::events::NodeStartEvent;
+
+use crate::{
+    screens::{Area, Screen},
+    AppSet,
+};
+
+use super::{
+    difficulty::Difficulty,
+    ending::Ending,
+    inventory::{Item, ItemPickedUp},
+    player::Player,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RunStats>();
+    app.init_resource::<RunStats>();
+    app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
+        commands.insert_resource(RunStats::default())
+    });
+    app.add_systems(
+        Update,
+        (
+            tick_run_time.in_set(AppSet::TickTimers),
+            (record_distance, record_dialogues, record_area_transitions).in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(record_pickup);
+}
+
+/// Everything that happened during the current run.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub time: Duration,
+    /// Distance the player walked in pixels.
+    pub distance: f32,
+    pub items_picked_up: Vec<Item>,
+    pub dialogues: u32,
+    /// How often the player talked to the wife.
+    pub wife_asked: u32,
+    pub area_transitions: u32,
+    last_position: Option<Vec2>,
+}
+
+impl RunStats {
+    /// How much respect the run earned with the given ending.
+    pub fn respect(&self, ending: Ending, difficulty: Difficulty) -> u32 {
+        let base = match ending {
+            Ending::Good | Ending::Love | Ending::Portrait | Ending::Name | Ending::Picture => {
+                1000.0
+            }
+            Ending::Dino => 300.0,
+            Ending::Stab => 0.0,
+        };
+        let items = 50.0 * self.items_picked_up.len() as f32;
+        // She only likes being asked so many times.
+        let wife = 25.0 * self.wife_asked.min(4) as f32;
+        // Dawdling costs respect, but never more than the run earned.
+        let time_penalty = self.time.as_secs_f32();
+        let score = (base + items + wife - time_penalty).max(0.0);
+        (score * difficulty.score_multiplier()).round() as u32
+    }
+}
+
+/// Moving further than this in a single frame means the player was teleported.
+const TELEPORT_DISTANCE: f32 = 200.0;
+
+fn tick_run_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time += time.delta();
+}
+
+fn record_distance(
+    player: Query<&Transform, (With<Player>, Changed<Transform>)>,
+    mut stats: ResMut<RunStats>,
+) {
+    let Ok(transform) = player.get_single() else {
+        return;
+    };
+    let position = transform.translation.xy();
+    let step = stats
+        .last_position
+        .map_or(0.0, |last_position| last_position.distance(position));
+    // Wrapping around to the other area is not walking.
+    if step < TELEPORT_DISTANCE {
+        stats.distance += step;
+    }
+    stats.last_position = Some(position);
+}
+
+fn record_pickup(trigger: Trigger<ItemPickedUp>, mut stats: ResMut<RunStats>) {
+    stats.items_picked_up.push(trigger.event().0.clone());
+}
+
+fn record_dialogues(mut events: EventReader<NodeStartEvent>, mut stats: ResMut<RunStats>) {
+    for event in events.read() {
+        stats.dialogues += 1;
+        if event.node_name == "Wife" {
+            stats.wife_asked += 1;
+        }
+    }
+}
+
+fn record_area_transitions(
+    mut events: EventReader<StateTransitionEvent<Area>>,
+    mut stats: ResMut<RunStats>,
+) {
+    for event in events.read() {
+        // Entering the first area when the run starts is not a transition.
+        if event.exited.is_some() && event.entered.is_some() && event.exited != event.entered {
+            stats.area_transitions += 1;
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Trigger zones that start dialogue or scripted events when the player
+//! enters a region of the current area.
+//!
+//! Zones are spawned from the [`AreaLayout`](super::level::AreaLayout) of an
+//! area and are only re-evaluated when the player moves or the inventory
+//! changes, instead of every scripted event polling on its own.
+
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::{DialogueRunner, YarnValue};
+use serde::Deserialize;
+
+use crate::{screens::Screen, AppSet};
+
+use super::{
+    inventory::{Inventory, Item},
+    movement::{ActionsFrozen, FreezeReason},
+    player::Player,
+    speech::{find_speaker, Say},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TriggerZone>();
+    app.register_type::<FiredTriggers>();
+    app.init_resource::<FiredTriggers>();
+
+    app.add_systems(
+        Update,
+        check_trigger_zones
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.observe(run_zone_action);
+    app.observe(log_zone_event);
+
+    app.add_systems(OnEnter(Screen::Gameplay), |mut commands: Commands| {
+        commands.insert_resource(FiredTriggers::default())
+    });
+}
+
+/// A region in world space that does something once the player is inside of
+/// it and all of its conditions hold.
+#[derive(Component, Reflect, Debug, Clone, Deserialize)]
+#[reflect(Component)]
+pub struct TriggerZone {
+    /// Unique name, used to remember once-only zones across area changes.
+    pub name: String,
+    /// The region the player has to be in, in world units.
+    pub area: Rect,
+    #[serde(default)]
+    pub conditions: Vec<TriggerCondition>,
+    #[serde(default)]
+    pub repeat: TriggerRepeat,
+    pub action: TriggerAction,
+    /// Whether the zone fired since the player last entered it.
+    #[serde(skip)]
+    occupied: bool,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Deserialize)]
+pub enum TriggerCondition {
+    HasItem(Item),
+    LacksItem(Item),
+    /// A Yarn variable (including the leading `$`) has to be set to the given
+    /// value. Unset variables count as `false`.
+    YarnFlag(String, bool),
+}
+
+impl TriggerCondition {
+    fn holds(&self, inventory: &Inventory, dialogue_runner: &DialogueRunner) -> bool {
+        self.holds_for(
+            |item| inventory.items.contains(item),
+            |name| dialogue_runner.variable_storage().get(name).ok(),
+        )
+    }
+
+    /// Whether the condition holds, given whether the player has an item
+    /// and the value of a Yarn variable, if it is set.
+    pub fn holds_for(
+        &self,
+        has_item: impl Fn(&Item) -> bool,
+        variable: impl Fn(&str) -> Option<YarnValue>,
+    ) -> bool {
+        match self {
+            TriggerCondition::HasItem(item) => has_item(item),
+            TriggerCondition::LacksItem(item) => !has_item(item),
+            TriggerCondition::YarnFlag(name, expected) => {
+                variable(name).unwrap_or(YarnValue::Boolean(false)) == YarnValue::Boolean(*expected)
+            }
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TriggerRepeat {
+    /// Fire at most once per run, even if the area is entered again.
+    #[default]
+    Once,
+    /// Fire every time the player enters the zone.
+    Repeatable,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Deserialize)]
+pub enum TriggerAction {
+    /// Start the given Yarn node.
+    StartNode(String),
+    /// Trigger a [`ZoneEvent`] with the given name for Rust-side scripting.
+    Event(String),
+    /// Show a speech bubble above the character with the given [`Name`].
+    Say { speaker: String, text: String },
+}
+
+/// Event triggered by zones with a [`TriggerAction::Event`] action.
+#[derive(Event, Debug, Clone)]
+pub struct ZoneEvent(pub String);
+
+/// Event triggered on a [`TriggerZone`] entity when it fires.
+#[derive(Event, Debug)]
+struct ZoneEntered;
+
+/// Names of the once-only zones that already fired during this run.
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct FiredTriggers(Vec<String>);
+
+fn check_trigger_zones(
+    mut commands: Commands,
+    player: Query<Ref<Transform>, With<Player>>,
+    mut zones: Query<(Entity, &mut TriggerZone)>,
+    mut fired: ResMut<FiredTriggers>,
+    inventory: Res<Inventory>,
+    dialogue_runner: Query<&DialogueRunner>,
+    actions_frozen: Res<ActionsFrozen>,
+) {
+    if actions_frozen.is_frozen() {
+        return;
+    }
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let zone_added = zones.iter_mut().any(|(_, zone)| zone.is_added());
+    if !player.is_changed() && !inventory.is_changed() && !zone_added {
+        return;
+    }
+    let Ok(dialogue_runner) = dialogue_runner.get_single() else {
+        return;
+    };
+
+    let position = player.translation.xy();
+    for (entity, mut zone) in &mut zones {
+        if !zone.area.contains(position) {
+            if zone.occupied {
+                zone.occupied = false;
+            }
+            continue;
+        }
+        if zone.occupied
+            || zone.repeat == TriggerRepeat::Once && fired.0.contains(&zone.name)
+            || !zone
+                .conditions
+                .iter()
+                .all(|condition| condition.holds(&inventory, dialogue_runner))
+        {
+            continue;
+        }
+
+        zone.occupied = true;
+        if !fired.0.contains(&zone.name) {
+            fired.0.push(zone.name.clone());
+        }
+        commands.trigger_targets(ZoneEntered, entity);
+    }
+}
+
+fn run_zone_action(
+    trigger: Trigger<ZoneEntered>,
+    mut commands: Commands,
+    zones: Query<&TriggerZone>,
+    names: Query<(Entity, &Name)>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) {
+    let zone = zones
+        .get(trigger.entity())
+        .expect("zone entered event targets a trigger zone");
+    match &zone.action {
+        TriggerAction::StartNode(node) => {
+            let (runner, mut dialogue_runner) = dialogue_runner
+                .get_single_mut()
+                .expect("only one dialogue runner");
+            dialogue_runner.start_node(node);
+            actions_frozen.freeze(FreezeReason::Dialogue, runner);
+        }
+        TriggerAction::Event(name) => commands.trigger(ZoneEvent(name.clone())),
+        TriggerAction::Say { speaker, text } => {
+            if let Some(entity) = find_speaker(&names, speaker) {
+                commands.trigger_targets(Say::new(text.clone()), entity);
+            }
+        }
+    }
+}
+
+/// Nothing reacts to zone events yet, so they are only logged.
+fn log_zone_event(trigger: Trigger<ZoneEvent>) {
+    debug!("zone event {}", trigger.event().0);
+}
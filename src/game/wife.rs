@@ -3,25 +3,24 @@ use std::time::Duration;
 use bevy::{
     math::bounding::{Aabb2d, IntersectsVolume},
     prelude::*,
-    render::{
-        primitives::Aabb,
-        texture::{ImageLoaderSettings, ImageSampler},
-    },
+    render::primitives::Aabb,
 };
 use bevy_yarnspinner::prelude::DialogueRunner;
 
 use super::{
     animation::{Animation, AnimationData, AnimationState},
-    movement::ActionsFrozen,
-    player::Player,
+    movement::{ActionsFrozen, FreezeReason, MovementController},
+    npc::{NpcBehavior, RoutineStep},
+    player::{Player, PlayerAssets},
 };
 use crate::{
-    asset_tracking::LoadResource,
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
     screens::{Area, Screen},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<WifeAssets>();
+    app.load_collection::<WifeAssets>();
 
     app.add_systems(Update, talk.run_if(in_state(Screen::Gameplay)));
     app.observe(start_wife_dialogue);
@@ -32,12 +31,13 @@ pub struct Wife;
 
 pub fn spawn_wife(
     mut commands: Commands,
-    player_assets: Res<WifeAssets>,
+    wife_assets: Res<WifeAssets>,
+    player_assets: Res<PlayerAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
     let layout = TextureAtlasLayout::from_grid(
         UVec2::new(15, 21),
-        4,
+        2,
         1,
         Some(UVec2::splat(2)),
         Some(UVec2::splat(1)),
@@ -49,22 +49,53 @@ pub fn spawn_wife(
         state: AnimationState::Idling,
         atlas_index: 0,
     };
-    let player_animation = Animation::new(vec![idle]);
+    // The wife only has two frames, so she walks by idling faster.
+    let walk = AnimationData {
+        frames: 2,
+        interval: Duration::from_millis(100),
+        state: AnimationState::Walking,
+        atlas_index: 0,
+    };
+    let wife_animation = Animation::new(vec![idle, walk]);
+    let behavior = NpcBehavior::new(vec![
+        RoutineStep::Idle(Duration::from_secs(4)),
+        // Tend to the fire for a while.
+        RoutineStep::WalkTo(-170.0),
+        RoutineStep::Idle(Duration::from_secs(6)),
+        RoutineStep::WalkTo(150.0),
+        RoutineStep::Idle(Duration::from_secs(3)),
+        RoutineStep::WalkTo(-400.0),
+    ])
+    .with_notice_distance(200.0)
+    .with_barks(
+        vec![
+            "Hm hm hmmm...".to_string(),
+            "Fire need wood.".to_string(),
+            "Where man go?".to_string(),
+        ],
+        Duration::from_secs(12),
+        Some(player_assets.wife_hm.clone()),
+    );
 
     commands.spawn((
         Name::new("Wife"),
         Wife,
         SpriteBundle {
-            texture: player_assets.wife.clone(),
+            texture: wife_assets.wife.clone(),
             transform: Transform::from_scale(Vec2::splat(8.0).extend(1.0))
                 .with_translation(Vec3::new(-400.0, -78.0, 0.0)),
             ..Default::default()
         },
         TextureAtlas {
             layout: texture_atlas_layout.clone(),
-            index: player_animation.get_atlas_index(),
+            index: wife_animation.get_atlas_index(),
         },
-        player_animation,
+        wife_animation,
+        MovementController {
+            max_speed: 120.0,
+            ..default()
+        },
+        behavior,
         StateScoped(Area::Cave),
     ));
 }
@@ -105,35 +136,26 @@ struct DialogueStart;
 fn start_wife_dialogue(
     _trigger: Trigger<DialogueStart>,
     mut actions_frozen: ResMut<ActionsFrozen>,
-    mut dialogue_runner: Query<&mut DialogueRunner>,
+    mut dialogue_runner: Query<(Entity, &mut DialogueRunner)>,
 ) {
-    let mut dialogue_runner = dialogue_runner
+    let (runner, mut dialogue_runner) = dialogue_runner
         .get_single_mut()
         .expect("only one dialogue runner");
     dialogue_runner.start_node("Wife");
-    actions_frozen.freeze();
+    actions_frozen.freeze(FreezeReason::Dialogue, runner);
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct WifeAssets {
-    #[dependency]
     pub wife: Handle<Image>,
 }
 
-impl WifeAssets {
-    pub const PATH_WIFE: &'static str = "images/wife.png";
-}
+impl FromCollection for WifeAssets {
+    const COLLECTION: &'static str = "wife";
 
-impl FromWorld for WifeAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            wife: assets.load_with_settings(
-                WifeAssets::PATH_WIFE,
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-        }
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            wife: collection.image("wife")?,
+        })
     }
 }
@@ -1,21 +1,48 @@
+mod asset_manifest;
 mod asset_tracking;
 pub mod audio;
+pub mod definitions;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod dialogue;
 mod game;
+pub mod localization;
+#[cfg(not(target_family = "wasm"))]
+mod mods;
+mod persistence;
+pub mod puzzle;
+mod replay;
 mod screens;
 mod theme;
+pub mod validate;
+mod yarn_script;
+
+use std::time::Duration;
 
 use bevy::{
+    app::ScheduleRunnerPlugin,
     asset::AssetMetaCheck,
     audio::{AudioPlugin, Volume},
     prelude::*,
+    render::{
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
     window::WindowResolution,
+    winit::WinitPlugin,
 };
 use bevy_tweening::TweeningPlugin;
 
-pub struct AppPlugin;
+pub use replay::Replay;
+
+#[derive(Default)]
+pub struct AppPlugin {
+    /// Play back this replay instead of taking input.
+    pub replay: Option<Replay>,
+    /// Run without a window or renderer to check the replay. Requires a
+    /// [`Self::replay`], as nothing could end the app otherwise.
+    pub headless: bool,
+}
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
@@ -26,45 +53,84 @@ impl Plugin for AppPlugin {
 
         app.add_systems(Startup, spawn_camera);
 
-        app.add_plugins(
-            DefaultPlugins
-                .set(AssetPlugin {
-                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
-                    // This causes errors and even panics on web build on itch.
-                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
-                    meta_check: AssetMetaCheck::Never,
+        // Mods have to replace the default asset source before the asset
+        // plugin is added.
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let mods = mods::Mods::discover(&mods::mods_path());
+            if !mods.mods.is_empty() {
+                app.register_asset_source(
+                    bevy::asset::io::AssetSourceId::Default,
+                    mods.asset_source(),
+                );
+            }
+            app.insert_resource(mods);
+        }
+
+        let plugins = DefaultPlugins
+            .set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics on web build on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: Window {
+                    title: "Fishstick Jam 2".to_string(),
+                    canvas: Some("#bevy".to_string()),
+                    fit_canvas_to_parent: true,
+                    prevent_default_event_handling: true,
+                    resizable: false,
+                    resolution: WindowResolution::default().with_scale_factor_override(1.0),
                     ..default()
-                })
-                .set(WindowPlugin {
-                    primary_window: Window {
-                        title: "Fishstick Jam 2".to_string(),
-                        canvas: Some("#bevy".to_string()),
-                        fit_canvas_to_parent: true,
-                        prevent_default_event_handling: true,
-                        resizable: false,
-                        resolution: WindowResolution::default().with_scale_factor_override(1.0),
+                }
+                .into(),
+                ..default()
+            })
+            .set(AudioPlugin {
+                global_volume: GlobalVolume {
+                    volume: Volume::new(0.3),
+                },
+                ..default()
+            });
+        if self.headless {
+            // Keep the window around for UI layout, but never open it.
+            app.add_plugins(
+                plugins
+                    .set(RenderPlugin {
+                        render_creation: RenderCreation::Automatic(WgpuSettings {
+                            backends: None,
+                            ..default()
+                        }),
                         ..default()
-                    }
-                    .into(),
-                    ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
-                }),
-        );
+                    })
+                    .disable::<WinitPlugin>(),
+            );
+            app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO));
+        } else {
+            app.add_plugins(plugins);
+        }
 
         app.add_plugins(TweeningPlugin);
 
+        if let Some(replay) = &self.replay {
+            app.insert_resource(replay::ReplayPlayer::new(replay.clone(), self.headless));
+        }
+
         app.add_plugins((
+            asset_manifest::plugin,
             asset_tracking::plugin,
             game::plugin,
             screens::plugin,
             theme::plugin,
             dialogue::plugin,
+            localization::plugin,
+            puzzle::plugin,
+            replay::plugin,
         ));
+        #[cfg(not(target_family = "wasm"))]
+        app.add_plugins(mods::plugin);
 
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
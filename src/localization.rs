@@ -0,0 +1,297 @@
+//! Translations of the UI and the dialogue.
+//!
+//! UI text is translated with the string tables in `assets/locale`, which are
+//! keyed by the English text. Code keeps writing English and looks it up in
+//! [`Localization`]. Dialogue lines are tagged with `#line:` ids and translated
+//! with Yarn Spinner's string tables in `assets/dialogue`. Text without a
+//! translation is shown in English.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use derive_more::derive::{Display, Error, From};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_tracking::LoadResource,
+    dialogue::discover_yarn_files,
+    persistence::{PersistResource, Persistent},
+    yarn_script::dialogue_lines,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<StringTable>();
+    app.init_asset_loader::<StringTableLoader>();
+    app.load_resource::<StringTables>();
+
+    app.register_type::<LanguageSettings>();
+    app.persist_resource::<LanguageSettings>();
+    app.init_resource::<Localization>();
+    app.add_systems(
+        Update,
+        update_localization.run_if(resource_exists::<StringTables>),
+    );
+}
+
+#[derive(Reflect, Debug, Display, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    #[display("Deutsch")]
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// The language the game is written in.
+    pub const BASE: Language = Language::English;
+
+    /// The language tag used in file names and by Yarn Spinner.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::German => "de-DE",
+        }
+    }
+
+    /// Path of the UI string table, relative to the assets folder.
+    pub fn string_table_path(self) -> String {
+        format!("locale/{}.strings.ron", self.code())
+    }
+
+    /// Path of Yarn Spinner's string table for the dialogue, relative to the
+    /// assets folder. The base language has none.
+    pub fn dialogue_strings_path(self) -> String {
+        format!("dialogue/{}.strings.csv", self.code())
+    }
+
+    /// The language after this one in [`Self::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Default, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct LanguageSettings {
+    pub language: Language,
+}
+
+impl Persistent for LanguageSettings {
+    const KEY: &'static str = "language_settings";
+}
+
+/// The UI text of the selected language.
+#[derive(Resource, Debug, Default)]
+pub struct Localization {
+    language: Language,
+    strings: HashMap<String, String>,
+}
+
+impl Localization {
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The translation of UI text written in English, or the text itself if
+    /// it has not been translated.
+    pub fn get<'a>(&'a self, text: &'a str) -> &'a str {
+        self.strings.get(text).map_or(text, String::as_str)
+    }
+}
+
+/// UI text of one language, by its English text.
+#[derive(Asset, TypePath, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct StringTable {
+    pub strings: HashMap<String, String>,
+}
+
+#[derive(Resource, Asset, Reflect, Clone)]
+struct StringTables {
+    /// One table for every language in [`Language::ALL`].
+    #[dependency]
+    tables: Vec<Handle<StringTable>>,
+}
+
+impl FromWorld for StringTables {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            tables: Language::ALL
+                .iter()
+                .map(|language| assets.load(language.string_table_path()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct StringTableLoader;
+
+#[derive(Debug, Display, Error, From)]
+enum StringTableError {
+    #[display("could not read string table: {_0}")]
+    Io(std::io::Error),
+    #[display("invalid string table: {_0}")]
+    Ron(ron::error::SpannedError),
+}
+
+impl AssetLoader for StringTableLoader {
+    type Asset = StringTable;
+    type Settings = ();
+    type Error = StringTableError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<StringTable, StringTableError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+/// Rebuild the [`Localization`] when the language is changed, the tables
+/// finished loading or one of them was hot reloaded.
+fn update_localization(
+    settings: Res<LanguageSettings>,
+    tables: Res<StringTables>,
+    string_tables: Res<Assets<StringTable>>,
+    mut events: EventReader<AssetEvent<StringTable>>,
+    mut localization: ResMut<Localization>,
+) {
+    let reloaded = events.read().count() > 0;
+    if !settings.is_changed() && !tables.is_added() && !reloaded {
+        return;
+    }
+    let strings = string_tables
+        .get(&tables.tables[settings.language.index()])
+        .map(|table| table.strings.clone())
+        .unwrap_or_default();
+    *localization = Localization {
+        language: settings.language,
+        strings,
+    };
+}
+
+/// A text that has not been translated yet.
+#[derive(Debug, Serialize)]
+pub struct Untranslated {
+    pub language: &'static str,
+    /// `ui` or `dialogue`.
+    pub kind: &'static str,
+    /// The English text for UI, or the `#line:` id for dialogue.
+    pub id: String,
+    pub english: String,
+    /// Left empty for the translator.
+    pub translation: String,
+    /// Where the translation goes for UI, or where the line is for dialogue.
+    pub location: String,
+}
+
+/// Every UI text and dialogue line in `assets` that is not translated to
+/// `language` yet. Dialogue lines without a `#line:` tag can't be translated
+/// and are left out, the content validator reports them.
+pub fn find_untranslated(assets: &Path, language: Language) -> Result<Vec<Untranslated>, String> {
+    let mut untranslated = Vec::new();
+    if language == Language::BASE {
+        return Ok(untranslated);
+    }
+
+    let english = read_string_table(assets, Language::BASE)?;
+    let translated = read_string_table(assets, language)?;
+    for text in english.keys() {
+        if !translated.contains_key(text) {
+            untranslated.push(Untranslated {
+                language: language.code(),
+                kind: "ui",
+                id: text.clone(),
+                english: text.clone(),
+                translation: String::new(),
+                location: language.string_table_path(),
+            });
+        }
+    }
+
+    let translated = read_dialogue_strings(assets, language)?;
+    let files = discover_yarn_files(assets)
+        .map_err(|error| format!("could not list dialogue files: {error}"))?;
+    for file in files {
+        let source = fs::read_to_string(assets.join(&file))
+            .map_err(|error| format!("could not read {file}: {error}"))?;
+        for line in dialogue_lines(&source) {
+            let Some(id) = line.id else {
+                continue;
+            };
+            if !translated.contains_key(&id) {
+                untranslated.push(Untranslated {
+                    language: language.code(),
+                    kind: "dialogue",
+                    id,
+                    english: line.text,
+                    translation: String::new(),
+                    location: format!("{file}:{} ({})", line.line, line.node),
+                });
+            }
+        }
+    }
+    Ok(untranslated)
+}
+
+/// The UI string table of `language` in `assets`, or an empty one if there is
+/// none yet.
+pub fn read_string_table(
+    assets: &Path,
+    language: Language,
+) -> Result<BTreeMap<String, String>, String> {
+    let path = language.string_table_path();
+    match fs::read_to_string(assets.join(&path)) {
+        Ok(source) => ron::from_str(&source).map_err(|error| format!("invalid {path}: {error}")),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+/// The translated dialogue lines of `language` in `assets` by their id, or
+/// none if there is no string table yet.
+pub fn read_dialogue_strings(
+    assets: &Path,
+    language: Language,
+) -> Result<BTreeMap<String, String>, String> {
+    #[derive(Deserialize)]
+    struct Record {
+        id: String,
+        text: String,
+    }
+
+    let path = language.dialogue_strings_path();
+    let Ok(mut reader) = csv::Reader::from_path(assets.join(&path)) else {
+        return Ok(BTreeMap::new());
+    };
+    reader
+        .deserialize::<Record>()
+        .map(|record| {
+            record
+                .map(|record| (record.id, record.text))
+                .map_err(|error| format!("invalid {path}: {error}"))
+        })
+        .collect()
+}
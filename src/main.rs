@@ -1,9 +1,41 @@
 // Disable console on Windows for non-dev builds.
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
+use std::path::PathBuf;
+
 use bevy::prelude::*;
-use thanks_wife::AppPlugin;
+use thanks_wife::{AppPlugin, Replay};
 
 fn main() -> AppExit {
-    App::new().add_plugins(AppPlugin).run()
+    let mut plugin = AppPlugin::default();
+    let mut replay_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--replay" => match args.next() {
+                Some(path) => replay_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--replay needs the path of a replay file");
+                    return AppExit::error();
+                }
+            },
+            "--headless" => plugin.headless = true,
+            _ => eprintln!("ignoring unknown argument {arg}"),
+        }
+    }
+    if let Some(path) = replay_path {
+        match Replay::load(&path) {
+            Ok(replay) => plugin.replay = Some(replay),
+            Err(error) => {
+                eprintln!("{error}");
+                return AppExit::error();
+            }
+        }
+    }
+    // Nothing would ever end a headless run without a replay.
+    if plugin.headless && plugin.replay.is_none() {
+        eprintln!("--headless only works together with --replay");
+        return AppExit::error();
+    }
+    App::new().add_plugins(plugin).run()
 }
@@ -0,0 +1,347 @@
+//! Mods from the `mods` folder next to `assets`. Native builds only.
+//!
+//! Every mod is a folder laid out like `assets`. Its files are layered on top
+//! of the base game's with a custom default asset source, so a mod can
+//! replace any asset, including `game.manifest.ron` to swap sprites and
+//! sounds. Yarn files in its `dialogue` folder, items in its `items` folder
+//! and additions to the area layouts in its `areas` folder are loaded
+//! together with the base game's, see [`definitions`](crate::definitions).
+//! Changed files are hot reloaded from every layer.
+//!
+//! Mods are loaded in the order listed in `mods/load_order.ron`, followed by
+//! the remaining ones in alphabetical order. Later mods win when two provide
+//! the same file, Yarn node or item, which is reported as a conflict.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    asset::io::{
+        file::FileAssetReader, AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder,
+        AssetWatcher, PathStream, Reader,
+    },
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    definitions::read_files,
+    dialogue::discover_yarn_files,
+    game::inventory::{Item, ItemDefinitions},
+    yarn_script::node_titles,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Mods>();
+    app.add_systems(Startup, log_mods);
+}
+
+/// The mods that were found at startup, in load order.
+#[derive(Resource, Debug, Default)]
+pub struct Mods {
+    pub mods: Vec<ModInfo>,
+    /// Problems with the mods, like two mods providing the same Yarn node.
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ModInfo {
+    pub name: String,
+    pub description: String,
+    /// The folder of the mod, laid out like `assets`.
+    pub path: PathBuf,
+}
+
+/// The optional `mod.ron` in a mod's folder.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ModFile {
+    name: Option<String>,
+    description: String,
+}
+
+/// The folder mods are installed in.
+pub fn mods_path() -> PathBuf {
+    FileAssetReader::get_base_path().join("mods")
+}
+
+/// The base game's assets.
+pub fn assets_path() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
+
+impl Mods {
+    /// Find all mods in `path` and check them for conflicts.
+    pub fn discover(path: &Path) -> Self {
+        let mut folders = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect::<Vec<_>>(),
+            // No mods installed.
+            Err(_) => return Self::default(),
+        };
+        folders.sort();
+
+        let mut problems = Vec::new();
+        let load_order = fs::read_to_string(path.join("load_order.ron"))
+            .ok()
+            .and_then(|data| match ron::from_str::<Vec<String>>(&data) {
+                Ok(load_order) => Some(load_order),
+                Err(error) => {
+                    problems.push(format!("ignoring invalid mods/load_order.ron: {error}"));
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let folder_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        folders.sort_by_key(|folder| {
+            let position = load_order
+                .iter()
+                .position(|name| *name == folder_name(folder));
+            (position.is_none(), position)
+        });
+
+        let mods = folders
+            .into_iter()
+            .map(|folder| {
+                let file = fs::read_to_string(folder.join("mod.ron"))
+                    .ok()
+                    .and_then(|data| ron::from_str::<ModFile>(&data).ok())
+                    .unwrap_or_default();
+                ModInfo {
+                    name: file.name.unwrap_or_else(|| folder_name(&folder)),
+                    description: file.description,
+                    path: folder,
+                }
+            })
+            .collect::<Vec<_>>();
+        problems.extend(find_conflicts(&mods));
+        Self { mods, problems }
+    }
+
+    /// An asset source that reads files from the mods before the base game.
+    pub fn asset_source(&self) -> AssetSourceBuilder {
+        // Later mods override earlier ones.
+        let mut layers = self
+            .mods
+            .iter()
+            .rev()
+            .map(|info| info.path.clone())
+            .collect::<Vec<_>>();
+        layers.push(assets_path());
+        let watched_layers = layers.clone();
+        AssetSource::build()
+            .with_reader(move || {
+                Box::new(LayeredAssetReader {
+                    layers: layers.iter().map(FileAssetReader::new).collect(),
+                })
+            })
+            .with_watcher(move |sender| {
+                let watchers = watched_layers
+                    .iter()
+                    .filter_map(|layer| {
+                        AssetSource::get_default_watcher(
+                            layer.to_string_lossy().into_owned(),
+                            WATCH_DEBOUNCE,
+                        )(sender.clone())
+                    })
+                    .collect::<Vec<_>>();
+                // Without the file watcher feature, nothing is watched.
+                (!watchers.is_empty()).then(|| {
+                    Box::new(LayeredAssetWatcher {
+                        _watchers: watchers,
+                    }) as _
+                })
+            })
+            .with_watch_warning(AssetSource::get_default_watch_warning())
+    }
+}
+
+/// Discovery runs before logging is set up, so log the result afterwards.
+fn log_mods(mods: Res<Mods>) {
+    for info in &mods.mods {
+        info!("loaded mod {}", info.name);
+    }
+    for problem in &mods.problems {
+        warn!("mod problem: {problem}");
+    }
+}
+
+/// How long to wait for more changes to a file before reloading it, like the
+/// default asset source does.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches every layer. A change to a file that a later layer replaces just
+/// reloads the same file again.
+struct LayeredAssetWatcher {
+    _watchers: Vec<Box<dyn AssetWatcher>>,
+}
+
+impl AssetWatcher for LayeredAssetWatcher {}
+
+/// Reads every file from the first layer that has it.
+struct LayeredAssetReader {
+    layers: Vec<FileAssetReader>,
+}
+
+impl AssetReader for LayeredAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        for layer in &self.layers {
+            match layer.read(path).await {
+                Err(AssetReaderError::NotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        for layer in &self.layers {
+            match layer.read_meta(path).await {
+                Err(AssetReaderError::NotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        for layer in &self.layers {
+            match layer.read_directory(path).await {
+                Err(AssetReaderError::NotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        for layer in &self.layers {
+            if let Ok(true) = layer.is_directory(path).await {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Files, Yarn nodes and items that more than one mod provides.
+fn find_conflicts(mods: &[ModInfo]) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    let mut files: BTreeMap<PathBuf, &str> = BTreeMap::new();
+    // Where every node and item is defined, starting with the base game.
+    let mut nodes: BTreeMap<String, (&str, String)> = BTreeMap::new();
+    for (title, file) in yarn_nodes(&assets_path()) {
+        nodes.insert(title, ("the base game", file));
+    }
+    let mut items: BTreeMap<Item, (&str, String)> = BTreeMap::new();
+    for (item, file) in item_ids(&assets_path()) {
+        items.insert(item, ("the base game", file));
+    }
+    for info in mods {
+        for file in list_files(&info.path, Path::new("")) {
+            if file == Path::new("mod.ron") {
+                continue;
+            }
+            if let Some(other) = files.insert(file.clone(), &info.name) {
+                conflicts.push(format!(
+                    "{} and {other} both provide {}, using the one from {}",
+                    info.name,
+                    file.display(),
+                    info.name
+                ));
+            }
+        }
+
+        for (title, file) in yarn_nodes(&info.path) {
+            // Replacing the file a node is in replaces the node, so only
+            // nodes from other files clash.
+            if let Some((other, other_file)) =
+                nodes.insert(title.clone(), (&info.name, file.clone()))
+            {
+                if other_file != file {
+                    conflicts.push(format!(
+                        "{} defines the Yarn node {title} in {file}, but {other} already does in {other_file}",
+                        info.name
+                    ));
+                }
+            }
+        }
+
+        for (item, file) in item_ids(&info.path) {
+            if let Some((other, other_file)) =
+                items.insert(item.clone(), (&info.name, file.clone()))
+            {
+                if other_file != file {
+                    conflicts.push(format!(
+                        "{} defines the item {item} in {file}, but {other} already does in {other_file}",
+                        info.name
+                    ));
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+/// The items defined in `root`, with the file each is in.
+fn item_ids(root: &Path) -> Vec<(Item, String)> {
+    let files = read_files::<ItemDefinitions>(root).unwrap_or_else(|error| {
+        warn!("{error}");
+        Vec::new()
+    });
+    files
+        .into_iter()
+        .flat_map(|(file, definitions)| {
+            definitions
+                .items
+                .into_iter()
+                .map(move |definition| (definition.id, file.clone()))
+        })
+        .collect()
+}
+
+/// The titles of the Yarn nodes in the dialogue folder of `root`, with the
+/// file each is in.
+fn yarn_nodes(root: &Path) -> Vec<(String, String)> {
+    let mut nodes = Vec::new();
+    for file in discover_yarn_files(root).unwrap_or_default() {
+        let Ok(source) = fs::read_to_string(root.join(&file)) else {
+            continue;
+        };
+        for title in node_titles(&source) {
+            nodes.push((title, file.clone()));
+        }
+    }
+    nodes
+}
+
+/// All files below `root.join(relative)`, relative to `root`.
+fn list_files(root: &Path, relative: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root.join(relative)) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = relative.join(entry.file_name());
+        if entry.path().is_dir() {
+            files.extend(list_files(root, &path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
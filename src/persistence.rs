@@ -0,0 +1,152 @@
+//! Data that is kept between runs of the game.
+//! Native builds write files into a `save` directory next to the executable,
+//! web builds use the browser's local storage.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::theme::prelude::*;
+
+/// A [`Resource`] that is loaded when the app starts and saved whenever it
+/// changes.
+pub trait Persistent: Resource + Serialize + DeserializeOwned + Default {
+    /// Unique name the resource is stored under.
+    const KEY: &'static str;
+
+    /// Text of the [`Toast`] shown whenever the resource was saved, if the
+    /// player should know about it.
+    const SAVED_TOAST: Option<&'static str> = None;
+
+    /// The least time between two saves, for resources that change all the
+    /// time. Changes in between are saved once it passed, or when the app
+    /// exits.
+    const SAVE_INTERVAL: Duration = Duration::ZERO;
+}
+
+pub trait PersistResource {
+    /// Insert the [`Persistent`] resource from storage, or its default if it
+    /// was never saved, and save it again every time it changes, at most
+    /// every [`Persistent::SAVE_INTERVAL`].
+    fn persist_resource<T: Persistent>(&mut self) -> &mut Self;
+}
+
+impl PersistResource for App {
+    fn persist_resource<T: Persistent>(&mut self) -> &mut Self {
+        let value = load::<T>().unwrap_or_default();
+        self.insert_resource(value);
+        self.add_systems(Last, save_resource::<T>);
+        self
+    }
+}
+
+#[derive(Default)]
+struct SaveState {
+    /// Whether the resource changed since it was last saved.
+    unsaved: bool,
+    last_saved: Option<Duration>,
+    failed: bool,
+}
+
+/// Save the resource, telling the player if it failed. They are only told
+/// once per resource, since a broken storage fails every time.
+fn save_resource<T: Persistent>(
+    value: Res<T>,
+    time: Res<Time<Real>>,
+    mut exit: EventReader<AppExit>,
+    mut commands: Commands,
+    mut state: Local<SaveState>,
+) {
+    if value.is_changed() && !value.is_added() {
+        state.unsaved = true;
+    }
+    let exiting = exit.read().count() > 0;
+    let waiting = state
+        .last_saved
+        .is_some_and(|last_saved| time.elapsed() - last_saved < T::SAVE_INTERVAL);
+    if !state.unsaved || (waiting && !exiting) {
+        return;
+    }
+    state.unsaved = false;
+    state.last_saved = Some(time.elapsed());
+    let data = match ron::ser::to_string_pretty(&*value, default()) {
+        Ok(data) => data,
+        Err(error) => {
+            warn!("could not serialize {}: {error}", T::KEY);
+            return;
+        }
+    };
+    match storage::write(&format!("{}.ron", T::KEY), &data) {
+        Ok(()) => {
+            if let Some(text) = T::SAVED_TOAST {
+                commands.trigger(Toast::new(text));
+            }
+        }
+        Err(error) => {
+            warn!("could not save {}: {error}", T::KEY);
+            if !state.failed {
+                state.failed = true;
+                commands.trigger(Toast::new("Could not save progress"));
+            }
+        }
+    }
+}
+
+/// Store a file that is not read by the game again, like an export.
+pub fn write_file(name: &str, data: &str) {
+    if let Err(error) = storage::write(name, data) {
+        warn!("could not write {name}: {error}");
+    }
+}
+
+fn load<T: Persistent>() -> Option<T> {
+    let data = storage::read(&format!("{}.ron", T::KEY))?;
+    match ron::from_str(&data) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("ignoring invalid saved {}: {error}", T::KEY);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod storage {
+    use std::{fs, io, path::PathBuf};
+
+    fn path(name: &str) -> io::Result<PathBuf> {
+        let exe = std::env::current_exe()?;
+        Ok(exe.parent().unwrap_or(&exe).join("save").join(name))
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        fs::read_to_string(path(name).ok()?).ok()
+    }
+
+    pub fn write(name: &str, data: &str) -> io::Result<()> {
+        let path = path(name)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, data)
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod storage {
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        local_storage()?.get_item(name).ok()?
+    }
+
+    pub fn write(name: &str, data: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("local storage is not available")?
+            .set_item(name, data)
+            .map_err(|error| format!("{error:?}"))
+    }
+}
@@ -0,0 +1,794 @@
+//! A model of the puzzle chain for checking it outside of the game.
+//!
+//! The dialogue is compiled and run by Yarn Spinner through
+//! [`yarn_script`](crate::yarn_script), and everything the game does with
+//! items comes from the definitions the game itself uses: the items and area
+//! layouts in `assets`, and the [recipes](crate::game::recipe). A
+//! search over every state the player can get into then shows which endings
+//! can be reached and where the player could get stuck. The dependency graph
+//! is read off the same search, and the game keeps it as [`PuzzleGraph`] to
+//! know which hint to give and which objectives are done.
+
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, VecDeque},
+    fmt::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+#[cfg(not(target_family = "wasm"))]
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
+use bevy_yarnspinner::prelude::YarnProject;
+
+use crate::{
+    definitions::read_definitions,
+    dialogue::load_yarn_files,
+    game::{
+        ending::Ending,
+        inventory::{Item, ItemDefinitions},
+        level::AreaLayouts,
+        recipe::{pickup_node, use_node, Place, Recipe, Station, FIRE_NODE},
+        trigger::{TriggerAction, TriggerRepeat},
+    },
+    yarn_script::{Program, YarnState, YarnValue},
+};
+
+/// The result of exploring every state of the puzzle.
+#[derive(Debug, Default)]
+pub struct PuzzleReport {
+    /// Number of distinct states the player can get into.
+    pub states: usize,
+    /// Endings together with the shortest list of actions reaching them.
+    pub reachable_endings: Vec<(String, Vec<String>)>,
+    pub unreachable_endings: Vec<String>,
+    /// Actions leading into states from which the good ending can't be
+    /// reached anymore, shortest first.
+    pub soft_locks: Vec<Vec<String>>,
+    /// Actions that fail in the game, like converting an item that isn't
+    /// there, with the actions leading to them.
+    pub errors: Vec<(String, Vec<String>)>,
+}
+
+impl PuzzleReport {
+    pub fn is_ok(&self) -> bool {
+        self.unreachable_endings.is_empty() && self.soft_locks.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// The name the player entered in the settings, as Yarn sees it.
+const PLAYER_NAME: &str = "PLAYER";
+
+/// What can be put on the paper: the words the dialogue reacts to, some
+/// other text, and drawings with and without text. `close_paper` passes them
+/// on as `$paper_text` and `$paper_drawn`.
+const PAPER_CONTENTS: [(&str, bool); 6] = [
+    ("LOVE", false),
+    ("WIFE", false),
+    (PLAYER_NAME, false),
+    ("HELLO", false),
+    ("HELLO", true),
+    ("", true),
+];
+
+/// The puzzle as defined by the game and the content in `assets`.
+pub struct Puzzle {
+    program: Program,
+    items: Arc<[Item]>,
+    layouts: AreaLayouts,
+}
+
+/// Every state reachable from the start of a run.
+struct Exploration {
+    states: Vec<PuzzleState>,
+    /// Index of the previous state and the action leading to each state.
+    parents: Vec<Option<(usize, String)>>,
+    /// The states each state leads to, and by which action.
+    edges: Vec<Vec<(usize, String)>>,
+    errors: Vec<(String, Vec<String>)>,
+}
+
+impl Puzzle {
+    pub fn load(assets: &Path) -> Result<Self, String> {
+        Ok(Self::new(
+            load_yarn_files(assets)?,
+            &read_definitions(assets)?,
+            read_definitions(assets)?,
+        ))
+    }
+
+    fn new(program: Program, items: &ItemDefinitions, layouts: AreaLayouts) -> Self {
+        Self {
+            program,
+            items: items.ids().cloned().collect(),
+            layouts,
+        }
+    }
+
+    /// Explore every state the player can reach from the start of a run.
+    pub fn check(&self) -> PuzzleReport {
+        let mut report = PuzzleReport::default();
+        let Exploration {
+            states,
+            parents,
+            edges,
+            errors,
+        } = match self.explore() {
+            Ok(exploration) => exploration,
+            Err(error) => {
+                report.errors.push((format!("Intro: {error}"), Vec::new()));
+                return report;
+            }
+        };
+        report.states = states.len();
+        report.errors = errors;
+
+        for ending in Ending::ALL {
+            match states.iter().position(|state| state.ending == Some(ending)) {
+                Some(index) => report
+                    .reachable_endings
+                    .push((ending.to_string(), path_to(&parents, index))),
+                None => report.unreachable_endings.push(ending.to_string()),
+            }
+        }
+
+        // Walk backwards from the good ending to find every state that can
+        // still get there.
+        let mut reverse = vec![Vec::new(); states.len()];
+        for (from, targets) in edges.iter().enumerate() {
+            for (to, _) in targets {
+                reverse[*to].push(from);
+            }
+        }
+        let mut can_finish = vec![false; states.len()];
+        let mut stack = (0..states.len())
+            .filter(|index| states[*index].ending.is_some_and(Ending::is_good))
+            .collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            if can_finish[index] {
+                continue;
+            }
+            can_finish[index] = true;
+            stack.extend(&reverse[index]);
+        }
+        // Only report where the player first gets stuck.
+        for (index, state) in states.iter().enumerate() {
+            let stuck = !can_finish[index] && state.ending.is_none();
+            let parent_stuck = parents[index]
+                .as_ref()
+                .is_some_and(|(parent, _)| !can_finish[*parent]);
+            if stuck && !parent_stuck {
+                report.soft_locks.push(path_to(&parents, index));
+            }
+        }
+        report.soft_locks.sort_by_key(Vec::len);
+        report
+    }
+
+    /// Breadth-first search from the states the intro can end in.
+    fn explore(&self) -> Result<Exploration, String> {
+        let mut start = PuzzleState {
+            items: self.items.clone(),
+            inventory: BTreeSet::new(),
+            level: self.layouts.start_items().into_iter().collect(),
+            variables: BTreeMap::new(),
+            fired: BTreeSet::new(),
+            ending: None,
+        };
+        start.variables.insert(
+            "$player_name".to_string(),
+            YarnValue::String(PLAYER_NAME.to_string()),
+        );
+        let mut exploration = Exploration {
+            states: Vec::new(),
+            parents: Vec::new(),
+            edges: Vec::new(),
+            errors: Vec::new(),
+        };
+        let mut indices = HashMap::new();
+        let mut queue = VecDeque::new();
+        for state in self.run("Intro", start)? {
+            if let Entry::Vacant(entry) = indices.entry(state.key()) {
+                entry.insert(exploration.states.len());
+                queue.push_back(exploration.states.len());
+                exploration.states.push(state);
+                exploration.parents.push(None);
+                exploration.edges.push(Vec::new());
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            if exploration.states[index].ending.is_some() {
+                continue;
+            }
+            for (action, result) in self.actions(&exploration.states[index]) {
+                let results = match result {
+                    Ok(results) => results,
+                    Err(error) => {
+                        let mut path = path_to(&exploration.parents, index);
+                        path.push(action.clone());
+                        exploration
+                            .errors
+                            .push((format!("{action}: {error}"), path));
+                        continue;
+                    }
+                };
+                for state in results {
+                    let next = *indices.entry(state.key()).or_insert_with(|| {
+                        queue.push_back(exploration.states.len());
+                        exploration.states.push(state);
+                        exploration.parents.push(Some((index, action.clone())));
+                        exploration.edges.push(Vec::new());
+                        exploration.states.len() - 1
+                    });
+                    exploration.edges[index].push((next, action.clone()));
+                }
+            }
+        }
+        Ok(exploration)
+    }
+
+    /// Everything the player can do in a state, and what it leads to.
+    fn actions(&self, state: &PuzzleState) -> Vec<(String, Result<Vec<PuzzleState>, String>)> {
+        let mut actions = Vec::new();
+
+        // Every area can be walked to at any time, so every item lying
+        // around can be picked up.
+        for item in &state.level {
+            let mut next = state.clone();
+            next.level.remove(item);
+            next.inventory.insert(item.clone());
+            let result = match pickup_node(item) {
+                Some(node) => self.run(node, next),
+                None => Ok(vec![next]),
+            };
+            actions.push((format!("pick up {item}"), result));
+        }
+
+        for item in &state.inventory {
+            if let Some(node) = use_node(item) {
+                actions.push((format!("use {item}"), self.run(&node, state.clone())));
+            }
+        }
+
+        // The fire burns the first thing it can, and only talks when there
+        // is nothing.
+        match Recipe::at(Station::Fire).find(|recipe| state.can_make(recipe)) {
+            Some(recipe) => actions.push((
+                format!("put {} in fire", recipe.input),
+                self.make(recipe, state.clone()),
+            )),
+            None => {
+                let burnt = Recipe::at(Station::Fire)
+                    .any(|recipe| state.level.iter().any(|item| item.id() == recipe.output));
+                if !burnt {
+                    actions.push((
+                        "walk into fire".to_string(),
+                        self.run(FIRE_NODE, state.clone()),
+                    ));
+                }
+            }
+        }
+
+        for recipe in Recipe::at(Station::Paper).filter(|recipe| state.can_make(recipe)) {
+            for (text, drawn) in PAPER_CONTENTS {
+                let mut next = state.clone();
+                next.variables.insert(
+                    "$paper_text".to_string(),
+                    YarnValue::String(text.to_string()),
+                );
+                next.variables
+                    .insert("$paper_drawn".to_string(), YarnValue::Boolean(drawn));
+                let action = match (text, drawn) {
+                    ("", _) => format!("draw on {}", recipe.input),
+                    (text, false) => format!("write {text:?} on {}", recipe.input),
+                    (text, true) => format!("write {text:?} and draw on {}", recipe.input),
+                };
+                actions.push((action, self.make(recipe, next)));
+            }
+        }
+
+        actions.push(("talk to Wife".to_string(), self.run("Wife", state.clone())));
+
+        for (_, layout) in self.layouts.iter() {
+            for zone in &layout.trigger_zones {
+                if zone.repeat == TriggerRepeat::Once && state.fired.contains(&zone.name) {
+                    continue;
+                }
+                let holds = zone.conditions.iter().all(|condition| {
+                    condition.holds_for(
+                        |item| state.inventory.contains(item),
+                        |name| {
+                            state
+                                .variables
+                                .get(name)
+                                .cloned()
+                                .or_else(|| self.program.initial_value(name))
+                        },
+                    )
+                });
+                if !holds {
+                    continue;
+                }
+                let mut next = state.clone();
+                next.fired.insert(zone.name.clone());
+                let result = match &zone.action {
+                    TriggerAction::StartNode(node) => self.run(node, next),
+                    TriggerAction::Event(_) | TriggerAction::Say { .. } => Ok(vec![next]),
+                };
+                actions.push((format!("enter zone {}", zone.name), result));
+            }
+        }
+        actions
+    }
+
+    fn run(&self, node: &str, mut state: PuzzleState) -> Result<Vec<PuzzleState>, String> {
+        state.sync_items();
+        self.program.run(node, state)
+    }
+
+    fn make(&self, recipe: &Recipe, mut state: PuzzleState) -> Result<Vec<PuzzleState>, String> {
+        state.inventory.remove(&Item::new(recipe.input));
+        match recipe.output_place {
+            Place::Inventory => state.inventory.insert(Item::new(recipe.output)),
+            Place::Level => state.level.insert(Item::new(recipe.output)),
+        };
+        match recipe.node {
+            Some(node) => self.run(node, state),
+            None => {
+                state.sync_items();
+                Ok(vec![state])
+            }
+        }
+    }
+
+    /// What is needed to get each item, flag and ending.
+    pub fn graph(&self) -> Result<PuzzleGraph, String> {
+        let exploration = self.explore()?;
+        let start_level = self.layouts.start_items();
+        let atoms = exploration
+            .states
+            .iter()
+            .map(|state| state.atoms(&start_level))
+            .collect::<Vec<_>>();
+        // What the player has in every state the intro can end in doesn't
+        // need to be got.
+        let mut initial: Option<BTreeSet<String>> = None;
+        for (index, parent) in exploration.parents.iter().enumerate() {
+            if parent.is_none() {
+                initial = Some(match initial {
+                    Some(initial) => initial.intersection(&atoms[index]).cloned().collect(),
+                    None => atoms[index].clone(),
+                });
+            }
+        }
+        let initial = initial.unwrap_or_default();
+
+        // Everything the player had each time they got something, and the
+        // first action that got it.
+        let mut requirements: BTreeMap<String, (BTreeSet<String>, String)> = BTreeMap::new();
+        for (from, targets) in exploration.edges.iter().enumerate() {
+            let before = &atoms[from];
+            for (to, action) in targets {
+                for atom in atoms[*to].difference(before) {
+                    requirements
+                        .entry(atom.clone())
+                        .and_modify(|(needed, _)| needed.retain(|need| before.contains(need)))
+                        .or_insert_with(|| {
+                            (
+                                before.difference(&initial).cloned().collect(),
+                                action.clone(),
+                            )
+                        });
+                }
+            }
+        }
+        Ok(PuzzleGraph { requirements })
+    }
+
+    /// The puzzle dependencies as a Graphviz graph: which items and flags
+    /// are needed to get which other items, flags and endings.
+    pub fn to_dot(&self) -> String {
+        let graph = self.graph().unwrap_or_default();
+        let mut edges = BTreeSet::new();
+        for (atom, (_, action)) in &graph.requirements {
+            let direct = graph.direct_needs(atom);
+            if direct.is_empty() {
+                edges.insert(("start", atom, action));
+            }
+            for need in direct {
+                edges.insert((need, atom, action));
+            }
+        }
+
+        let mut dot = String::from("digraph puzzle {\n    rankdir=LR;\n");
+        for (from, to, label) in edges {
+            writeln!(dot, "    {from:?} -> {to:?} [label={label:?}];").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// What the player needs to get each item, flag and ending, read off the
+/// search of the puzzle. Something counts as needed for something else if
+/// the player had it every time they got the other thing, in every state.
+///
+/// Items, flags and endings are named by [`atoms`].
+#[derive(Resource, Debug, Default)]
+pub struct PuzzleGraph {
+    /// Everything needed for each atom, and the first action that got it.
+    requirements: BTreeMap<String, (BTreeSet<String>, String)>,
+}
+
+impl PuzzleGraph {
+    /// What is needed for `atom`, leaving out what is only needed to get
+    /// another requirement.
+    pub fn direct_needs(&self, atom: &str) -> Vec<&str> {
+        let Some((needed, _)) = self.requirements.get(atom) else {
+            return Vec::new();
+        };
+        needed
+            .iter()
+            .filter(|need| {
+                !needed.iter().any(|other| {
+                    self.requirements
+                        .get(other)
+                        .is_some_and(|(other_needed, _)| other_needed.contains(*need))
+                })
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether `need` has to be got before `atom`, directly or not.
+    pub fn leads_to(&self, need: &str, atom: &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![atom];
+        while let Some(atom) = stack.pop() {
+            if !visited.insert(atom) {
+                continue;
+            }
+            if let Some((needed, _)) = self.requirements.get(atom) {
+                if needed.contains(need) {
+                    return true;
+                }
+                stack.extend(needed.iter().map(String::as_str));
+            }
+        }
+        false
+    }
+
+    /// Whether everything needed for `atom` is in `got`. Never true for
+    /// what can't be got at all.
+    pub fn can_get(&self, atom: &str, got: &BTreeSet<String>) -> bool {
+        self.requirements
+            .get(atom)
+            .is_some_and(|(needed, _)| needed.is_subset(got))
+    }
+}
+
+/// The items the player holds, the items lying in the level that weren't
+/// there at the start, and the flags that are set, as named in the
+/// [`PuzzleGraph`].
+pub fn atoms<'a>(
+    inventory: impl IntoIterator<Item = &'a Item>,
+    level: impl IntoIterator<Item = &'a Item>,
+    start_level: &[Item],
+    variables: impl IntoIterator<Item = (&'a String, &'a YarnValue)>,
+) -> BTreeSet<String> {
+    let held = inventory.into_iter().map(|item| held_atom(item.id()));
+    let dropped = level
+        .into_iter()
+        .filter(|item| !start_level.contains(item))
+        .map(|item| dropped_atom(item.id()));
+    let flags = variables.into_iter().filter_map(|(name, value)| {
+        let internal = name.starts_with("$_has_") || name.starts_with("$Yarn.");
+        (!internal && *value == YarnValue::Boolean(true)).then(|| name.clone())
+    });
+    held.chain(dropped).chain(flags).collect()
+}
+
+/// An item the player holds.
+pub fn held_atom(item: &str) -> String {
+    item.to_string()
+}
+
+/// An item lying in the level that wasn't there at the start.
+pub fn dropped_atom(item: &str) -> String {
+    format!("{item} on the ground")
+}
+
+/// An ending the player reached.
+pub fn ending_atom(ending: Ending) -> String {
+    format!("ending {ending}")
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, search_puzzle);
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(Update, finish_puzzle_search.after(search_puzzle));
+}
+
+/// The search of the puzzle that is running in the background. Until the
+/// first one finished there is no [`PuzzleGraph`].
+#[cfg(not(target_family = "wasm"))]
+#[derive(Resource)]
+struct PuzzleSearch(Task<Result<PuzzleGraph, String>>);
+
+/// Search the puzzle of the loaded content, again whenever it is reloaded,
+/// for the hints and the journal. A search that is still running for older
+/// content is dropped, which cancels it.
+fn search_puzzle(
+    mut commands: Commands,
+    project: Option<Res<YarnProject>>,
+    items: Option<Res<ItemDefinitions>>,
+    layouts: Option<Res<AreaLayouts>>,
+) {
+    let (Some(project), Some(items), Some(layouts)) = (project, items, layouts) else {
+        return;
+    };
+    if !(project.is_changed() || items.is_changed() || layouts.is_changed()) {
+        return;
+    }
+    let puzzle = Puzzle::new(
+        Program::from_compilation(project.compilation()),
+        &items,
+        layouts.clone(),
+    );
+    // Web builds have no threads to search on, so they search right away.
+    #[cfg(target_family = "wasm")]
+    insert_puzzle_graph(&mut commands, puzzle.graph());
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let task = AsyncComputeTaskPool::get().spawn(async move { puzzle.graph() });
+        commands.insert_resource(PuzzleSearch(task));
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn finish_puzzle_search(mut commands: Commands, search: Option<ResMut<PuzzleSearch>>) {
+    let Some(mut search) = search else {
+        return;
+    };
+    if let Some(graph) = block_on(poll_once(&mut search.0)) {
+        commands.remove_resource::<PuzzleSearch>();
+        insert_puzzle_graph(&mut commands, graph);
+    }
+}
+
+fn insert_puzzle_graph(commands: &mut Commands, graph: Result<PuzzleGraph, String>) {
+    match graph {
+        Ok(graph) => commands.insert_resource(graph),
+        Err(error) => warn!("could not search the puzzle: {error}"),
+    }
+}
+
+fn path_to(parents: &[Option<(usize, String)>], mut index: usize) -> Vec<String> {
+    let mut path = Vec::new();
+    while let Some((parent, action)) = &parents[index] {
+        path.push(action.clone());
+        index = *parent;
+    }
+    path.reverse();
+    path
+}
+
+#[derive(Debug, Clone)]
+struct PuzzleState {
+    /// Every defined item, to keep the variables in sync with.
+    items: Arc<[Item]>,
+    inventory: BTreeSet<Item>,
+    level: BTreeSet<Item>,
+    variables: BTreeMap<String, YarnValue>,
+    /// Trigger zones that only fire once and already did.
+    fired: BTreeSet<String>,
+    ending: Option<Ending>,
+}
+
+impl PuzzleState {
+    /// Identifies the state, as values can't be hashed.
+    fn key(&self) -> String {
+        format!(
+            "{:?}",
+            (
+                &self.inventory,
+                &self.level,
+                &self.variables,
+                &self.fired,
+                &self.ending
+            )
+        )
+    }
+
+    /// Set the variables the game keeps in sync with the inventory.
+    fn sync_items(&mut self) {
+        for item in self.items.iter() {
+            self.variables.insert(
+                format!("$_has_{item}"),
+                YarnValue::Boolean(self.inventory.contains(item)),
+            );
+        }
+    }
+
+    fn can_make(&self, recipe: &Recipe) -> bool {
+        self.inventory.contains(&Item::new(recipe.input))
+            && recipe
+                .is_unlocked(|name| self.variables.get(name) == Some(&YarnValue::Boolean(true)))
+    }
+
+    /// The items, flags and ending the player has got in this state.
+    fn atoms(&self, start_level: &[Item]) -> BTreeSet<String> {
+        let mut atoms = atoms(&self.inventory, &self.level, start_level, &self.variables);
+        atoms.extend(self.ending.map(ending_atom));
+        atoms
+    }
+}
+
+impl YarnState for PuzzleState {
+    fn variables(&mut self) -> &mut BTreeMap<String, YarnValue> {
+        &mut self.variables
+    }
+
+    fn command(&mut self, name: &str, args: &[String]) -> Result<(), String> {
+        let items = self.items.clone();
+        let item = |name: &String| {
+            items
+                .iter()
+                .find(|item| item.id() == name)
+                .cloned()
+                .ok_or(format!("unknown item {name}"))
+        };
+        match (name, args) {
+            ("inventory_convert", [from, to]) => {
+                if !self.inventory.remove(&item(from)?) {
+                    return Err(format!("{from} is not in the inventory"));
+                }
+                self.inventory.insert(item(to)?);
+            }
+            ("level_convert", [from, to]) => {
+                if !self.level.remove(&item(from)?) {
+                    return Err(format!("{from} is not in the level"));
+                }
+                self.level.insert(item(to)?);
+            }
+            ("drop", [dropped]) => {
+                let dropped = item(dropped)?;
+                if !self.inventory.remove(&dropped) {
+                    return Err(format!("{dropped} is not in the inventory"));
+                }
+                self.level.insert(dropped);
+            }
+            ("end_game", [id]) => {
+                self.ending =
+                    Some(Ending::from_id(id).ok_or_else(|| format!("unknown ending {id}"))?);
+            }
+            // Everything else doesn't change the puzzle.
+            _ => {}
+        }
+        self.sync_items();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Dialogue for the game's items in which burning the banana and
+    /// bringing it to the wife wins, and the knife eats the banana.
+    const DIALOGUE: &str = "\
+title: Intro
+---
+Hello.
+===
+title: Wife
+---
+<<if $_has_BurntBanana>>
+    <<end_game good>>
+<<endif>>
+===
+title: Fire
+---
+Warm.
+===
+title: DroppedBanana
+---
+Oops.
+===
+title: Papyrus
+---
+A plant.
+===
+title: Knife
+---
+<<if $_has_Banana>>
+    <<inventory_convert Banana Papyrus>>
+<<endif>>
+===
+title: Banana
+---
+Yum.
+===
+title: BurntBanana
+---
+Burnt.
+===
+";
+
+    fn assets() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+    }
+
+    /// The puzzle of the game with other dialogue.
+    fn puzzle(source: &str) -> Puzzle {
+        Puzzle::new(
+            Program::compile([("test.yarn".to_string(), source.to_string())]).unwrap(),
+            &read_definitions(&assets()).unwrap(),
+            read_definitions(&assets()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn game_puzzle_is_ok() {
+        let puzzle = Puzzle::load(&assets()).unwrap();
+        let report = puzzle.check();
+        assert!(report.is_ok(), "{report:#?}");
+    }
+
+    #[test]
+    fn finds_endings_and_soft_locks() {
+        let report = puzzle(DIALOGUE).check();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        let (ending, path) = &report.reachable_endings[0];
+        assert_eq!(ending, "Good");
+        assert_eq!(
+            path,
+            &[
+                "pick up Banana",
+                "put Banana in fire",
+                "pick up BurntBanana",
+                "talk to Wife"
+            ]
+        );
+        assert_eq!(report.unreachable_endings.len(), Ending::ALL.len() - 1);
+        assert_eq!(report.soft_locks[0].len(), 3);
+        assert_eq!(report.soft_locks[0].last().unwrap(), "use Knife");
+    }
+
+    #[test]
+    fn reports_failing_commands() {
+        let source = DIALOGUE.replace("A plant.", "<<inventory_convert Knife Paper>>");
+        let report = puzzle(&source).check();
+        let (error, path) = &report.errors[0];
+        assert!(error.contains("Knife is not in the inventory"), "{error}");
+        assert_eq!(path, &["pick up Papyrus", "use Papyrus"]);
+    }
+
+    #[test]
+    fn graph_follows_the_chain() {
+        let dot = puzzle(DIALOGUE).to_dot();
+        assert!(
+            dot.contains(r#""start" -> "Banana" [label="pick up Banana"];"#),
+            "{dot}"
+        );
+        assert!(
+            dot.contains(
+                r#""Banana" -> "BurntBanana on the ground" [label="put Banana in fire"];"#
+            ),
+            "{dot}"
+        );
+        assert!(
+            dot.contains(
+                r#""BurntBanana on the ground" -> "BurntBanana" [label="pick up BurntBanana"];"#
+            ),
+            "{dot}"
+        );
+        assert!(
+            dot.contains(r#""BurntBanana" -> "ending Good" [label="talk to Wife"];"#),
+            "{dot}"
+        );
+    }
+}
@@ -0,0 +1,446 @@
+//! Recording and deterministic playback of runs.
+//!
+//! Every run is recorded as the frame times and device input of each frame,
+//! along with the seed of the [`GameRng`]. Playing a replay feeds the same
+//! frame times to [`Time`] and the same input to the input events, so that the
+//! run plays out exactly as it was recorded. Touch input is not recorded.
+//!
+//! Each frame also notes where the player is whenever that changed, which is
+//! what the ghost of the best run follows.
+//!
+//! A replay also stores the state the run ended in, which is compared after
+//! playback, so replays can be used as regression tests with `--headless`,
+//! like the ones in `tests/replays`.
+
+use std::{path::Path, time::Duration};
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        common_conditions::input_just_pressed,
+        keyboard::{Key, KeyboardInput},
+        mouse::MouseButtonInput,
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{CursorLeft, CursorMoved, PrimaryWindow},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{
+        difficulty::Difficulty,
+        ending::{Ending, ReachEnding, ReachedEnding},
+        inventory::{Inventory, Item},
+        player::Player,
+    },
+    persistence,
+    screens::{Area, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayRecorder>();
+    app.add_systems(
+        Startup,
+        prepare_replay.run_if(resource_exists::<ReplayPlayer>),
+    );
+    app.add_systems(OnEnter(Screen::Gameplay), start_run);
+    app.add_systems(
+        PreUpdate,
+        play_input
+            .before(InputSystem)
+            .run_if(resource_exists::<ReplayPlayer>),
+    );
+    app.add_systems(
+        Update,
+        request_save.run_if(in_state(Screen::Gameplay).and_then(input_just_pressed(KeyCode::F9))),
+    );
+    app.add_systems(
+        Last,
+        (
+            record_frame.run_if(not(resource_exists::<ReplayPlayer>)),
+            advance_replay.run_if(resource_exists::<ReplayPlayer>),
+        ),
+    );
+    app.observe(save_on_ending);
+}
+
+/// Random number generator for everything that affects gameplay. It is
+/// seeded at the start of every run so that replays make the same choices.
+#[derive(Resource, Debug)]
+pub struct GameRng(pub StdRng);
+
+/// A recorded run, stored as RON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub frames: Vec<ReplayFrame>,
+    /// The state at the end of the last frame.
+    pub outcome: ReplayOutcome,
+}
+
+impl Replay {
+    /// Read a replay file, explaining what is wrong with it if it can't be
+    /// played.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|error| format!("could not read replay {}: {error}", path.display()))?;
+        let replay: Replay = ron::from_str(&data)
+            .map_err(|error| format!("invalid replay {}: {error}", path.display()))?;
+        if replay.frames.is_empty() {
+            return Err(format!("replay {} has no frames", path.display()));
+        }
+        Ok(replay)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub delta: Duration,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<ReplayInput>,
+    /// Where the player is at the end of the frame, if that changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<PlayerSnapshot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub area: Area,
+    pub position: Vec2,
+    pub flip_x: bool,
+}
+
+/// Sent when a finished run was recorded, e.g. once an ending is reached.
+#[derive(Event, Debug)]
+pub struct RunRecorded(pub Replay);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplayInput {
+    Key {
+        key_code: KeyCode,
+        logical_key: Key,
+        pressed: bool,
+    },
+    MouseButton {
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// The cursor moved to this position in the window, or left it.
+    Cursor(Option<Vec2>),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    pub player_position: Option<Vec2>,
+    pub inventory: Vec<Item>,
+    pub ending: Option<Ending>,
+}
+
+impl ReplayOutcome {
+    /// Whether the outcomes match, allowing for rounding in the file.
+    fn matches(&self, other: &ReplayOutcome) -> bool {
+        let positions_match = match (self.player_position, other.player_position) {
+            (Some(a), Some(b)) => a.distance(b) < 0.01,
+            (a, b) => a == b,
+        };
+        positions_match && self.inventory == other.inventory && self.ending == other.ending
+    }
+}
+
+/// Records the current run, unless a replay is playing.
+#[derive(Resource, Debug, Default)]
+struct ReplayRecorder {
+    seed: u64,
+    frames: Vec<ReplayFrame>,
+    recording: bool,
+    last_cursor: Option<Vec2>,
+    last_player: Option<PlayerSnapshot>,
+    save_requested: bool,
+    /// Stop recording once the requested save is done.
+    stop_after_save: bool,
+}
+
+/// Plays back a [`Replay`] from the moment gameplay starts.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    frame: usize,
+    started: bool,
+    /// Whether there is no window, so that the app exits with the result of
+    /// the comparison when done.
+    headless: bool,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay, headless: bool) -> Self {
+        Self {
+            replay,
+            frame: 0,
+            started: false,
+            headless,
+        }
+    }
+
+    /// Whether the replay is checked without a window, in which case the app
+    /// exits once it is done.
+    pub fn headless(&self) -> bool {
+        self.headless
+    }
+}
+
+const REPLAY_FILE: &str = "replay.ron";
+
+fn prepare_replay(mut commands: Commands, player: Res<ReplayPlayer>) {
+    commands.insert_resource(player.replay.difficulty);
+    commands.insert_resource(TimeUpdateStrategy::ManualDuration(
+        player.replay.frames[0].delta,
+    ));
+}
+
+fn start_run(
+    mut commands: Commands,
+    mut recorder: ResMut<ReplayRecorder>,
+    player: Option<ResMut<ReplayPlayer>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    let seed = match player {
+        Some(mut player) => {
+            player.started = true;
+            player.replay.seed
+        }
+        None => {
+            let seed = rand::thread_rng().gen();
+            *recorder = ReplayRecorder {
+                seed,
+                recording: true,
+                ..default()
+            };
+            seed
+        }
+    };
+    commands.insert_resource(GameRng(StdRng::seed_from_u64(seed)));
+    // Start every run with the same fixed timestep state, no matter how long
+    // loading took.
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+}
+
+fn record_frame(
+    mut commands: Commands,
+    mut recorder: ResMut<ReplayRecorder>,
+    time: Res<Time<Real>>,
+    mut keys: EventReader<KeyboardInput>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    player: Query<(&Transform, &Sprite), With<Player>>,
+    area: Option<Res<State<Area>>>,
+    difficulty: Res<Difficulty>,
+    outcome: OutcomeParams,
+) {
+    // Always read the events, so that only this frame's are recorded once
+    // recording starts.
+    let mut inputs = keys
+        .read()
+        .map(|event| ReplayInput::Key {
+            key_code: event.key_code,
+            logical_key: event.logical_key.clone(),
+            pressed: event.state.is_pressed(),
+        })
+        .chain(mouse_buttons.read().map(|event| ReplayInput::MouseButton {
+            button: event.button,
+            pressed: event.state.is_pressed(),
+        }))
+        .collect::<Vec<_>>();
+    if !recorder.recording {
+        return;
+    }
+    let cursor = window.get_single().ok().and_then(Window::cursor_position);
+    if cursor != recorder.last_cursor {
+        recorder.last_cursor = cursor;
+        inputs.push(ReplayInput::Cursor(cursor));
+    }
+    let snapshot = player
+        .get_single()
+        .ok()
+        .zip(area)
+        .map(|((transform, sprite), area)| PlayerSnapshot {
+            area: *area.get(),
+            position: transform.translation.xy(),
+            flip_x: sprite.flip_x,
+        });
+    let player = snapshot.filter(|snapshot| recorder.last_player != Some(*snapshot));
+    if player.is_some() {
+        recorder.last_player = player;
+    }
+    recorder.frames.push(ReplayFrame {
+        delta: time.delta(),
+        inputs,
+        player,
+    });
+
+    if recorder.save_requested {
+        recorder.save_requested = false;
+        let replay = Replay {
+            seed: recorder.seed,
+            difficulty: *difficulty,
+            frames: std::mem::take(&mut recorder.frames),
+            outcome: outcome.get(),
+        };
+        match ron::to_string(&replay) {
+            Ok(data) => persistence::write_file(REPLAY_FILE, &data),
+            Err(error) => warn!("could not serialize replay: {error}"),
+        }
+        if recorder.stop_after_save {
+            recorder.recording = false;
+            commands.trigger(RunRecorded(replay));
+        } else {
+            recorder.frames = replay.frames;
+        }
+    }
+}
+
+fn request_save(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.save_requested = recorder.recording;
+}
+
+fn save_on_ending(_: Trigger<ReachEnding>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.save_requested = recorder.recording;
+    recorder.stop_after_save = true;
+}
+
+/// Replace this frame's input with the recorded one. Without a window there
+/// is nothing to keep the cursor position of the [`Window`] up to date, so it
+/// is set as well. With one, that would move the player's actual cursor.
+fn play_input(
+    player: Res<ReplayPlayer>,
+    next_screen: Res<NextState<Screen>>,
+    mut keys: ResMut<Events<KeyboardInput>>,
+    mut mouse_buttons: ResMut<Events<MouseButtonInput>>,
+    mut cursor_moves: ResMut<Events<CursorMoved>>,
+    mut cursor_leaves: ResMut<Events<CursorLeft>>,
+    mut window: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+) {
+    // Recording starts in the frame gameplay is entered, which happens after
+    // input is handled.
+    let entering = matches!(*next_screen, NextState::Pending(Screen::Gameplay));
+    if !player.started && !entering {
+        return;
+    }
+    let Ok((window_entity, mut window)) = window.get_single_mut() else {
+        return;
+    };
+    keys.clear();
+    mouse_buttons.clear();
+    cursor_moves.clear();
+    cursor_leaves.clear();
+    let state = |pressed| {
+        if pressed {
+            ButtonState::Pressed
+        } else {
+            ButtonState::Released
+        }
+    };
+    for input in &player.replay.frames[player.frame].inputs {
+        match input {
+            ReplayInput::Key {
+                key_code,
+                logical_key,
+                pressed,
+            } => {
+                keys.send(KeyboardInput {
+                    key_code: *key_code,
+                    logical_key: logical_key.clone(),
+                    state: state(*pressed),
+                    window: window_entity,
+                });
+            }
+            ReplayInput::MouseButton { button, pressed } => {
+                mouse_buttons.send(MouseButtonInput {
+                    button: *button,
+                    state: state(*pressed),
+                    window: window_entity,
+                });
+            }
+            ReplayInput::Cursor(position) => {
+                match *position {
+                    Some(position) => {
+                        cursor_moves.send(CursorMoved {
+                            window: window_entity,
+                            position,
+                            delta: None,
+                        });
+                    }
+                    None => {
+                        cursor_leaves.send(CursorLeft {
+                            window: window_entity,
+                        });
+                    }
+                }
+                if player.headless {
+                    window.set_cursor_position(*position);
+                }
+            }
+        }
+    }
+}
+
+fn advance_replay(
+    mut commands: Commands,
+    mut player: ResMut<ReplayPlayer>,
+    mut app_exit: EventWriter<AppExit>,
+    outcome: OutcomeParams,
+) {
+    if !player.started {
+        return;
+    }
+    player.frame += 1;
+    if let Some(frame) = player.replay.frames.get(player.frame) {
+        commands.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+        return;
+    }
+
+    let outcome = outcome.get();
+    let matches = outcome.matches(&player.replay.outcome);
+    if matches {
+        info!("replay finished as recorded");
+    } else {
+        error!(
+            "replay diverged, expected {:?} but got {:?}",
+            player.replay.outcome, outcome
+        );
+    }
+    if player.headless {
+        app_exit.send(if matches {
+            AppExit::Success
+        } else {
+            AppExit::error()
+        });
+    }
+    commands.remove_resource::<ReplayPlayer>();
+    commands.insert_resource(TimeUpdateStrategy::Automatic);
+}
+
+#[derive(SystemParam)]
+struct OutcomeParams<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    inventory: Res<'w, Inventory>,
+    ending: Res<'w, ReachedEnding>,
+}
+
+impl OutcomeParams<'_, '_> {
+    fn get(&self) -> ReplayOutcome {
+        ReplayOutcome {
+            player_position: self
+                .player
+                .get_single()
+                .ok()
+                .map(|transform| transform.translation.xy()),
+            inventory: self.inventory.items.clone(),
+            ending: self.ending.0,
+        }
+    }
+}
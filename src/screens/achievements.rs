@@ -0,0 +1,68 @@
+//! A screen listing the achievements, accessed from the title screen.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        achievements::{Achievement, UnlockedAchievements},
+        ducks::{duck_count, FoundDucks},
+        level::AreaLayouts,
+    },
+    localization::Localization,
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Achievements), spawn_achievements_screen);
+}
+
+fn spawn_achievements_screen(
+    mut commands: Commands,
+    unlocked: Res<UnlockedAchievements>,
+    found_ducks: Res<FoundDucks>,
+    layouts: Res<AreaLayouts>,
+    localization: Res<Localization>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Achievements))
+        .with_children(|children| {
+            children.header(localization.get("Achievements"));
+            for achievement in Achievement::ALL {
+                let text = if unlocked.contains(achievement) {
+                    format!(
+                        "[x] {}: {}",
+                        localization.get(achievement.title()),
+                        localization.get(achievement.description())
+                    )
+                } else if achievement.hidden() {
+                    format!("[ ] {}", localization.get("???"))
+                } else {
+                    format!(
+                        "[ ] {}: {}",
+                        localization.get(achievement.title()),
+                        localization.get(achievement.description())
+                    )
+                };
+                children.label(text).insert(Style {
+                    width: Val::Px(700.0),
+                    ..default()
+                });
+            }
+            children.label(format!(
+                "{}: {}/{}",
+                localization.get("Rubber ducks found"),
+                found_ducks.ids.len(),
+                duck_count(&layouts)
+            ));
+
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
+        });
+}
+
+fn enter_title_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
@@ -2,26 +2,35 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::Music, screens::Screen, theme::prelude::*};
+use crate::{
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    audio::Music,
+    localization::Localization,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Credits), spawn_credits_screen);
 
-    app.load_resource::<CreditsMusic>();
+    app.load_collection::<CreditsMusic>();
     app.add_systems(OnEnter(Screen::Credits), play_credits_music);
     app.add_systems(OnExit(Screen::Credits), stop_music);
 }
 
-fn spawn_credits_screen(mut commands: Commands) {
+fn spawn_credits_screen(mut commands: Commands, localization: Res<Localization>) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Credits))
         .with_children(|children| {
-            children.header("Made by");
+            children.header(localization.get("Made by"));
             children.label("Jokler - Code");
             children.label("masha - Design");
 
-            children.button("Back").observe(enter_title_screen);
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
         });
 }
 
@@ -29,20 +38,20 @@ fn enter_title_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextSt
     next_screen.set(Screen::Title);
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct CreditsMusic {
-    #[dependency]
     music: Handle<AudioSource>,
     entity: Option<Entity>,
 }
 
-impl FromWorld for CreditsMusic {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/music/credits.ogg"),
+impl FromCollection for CreditsMusic {
+    const COLLECTION: &'static str = "credits";
+
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            music: collection.audio("music")?,
             entity: None,
-        }
+        })
     }
 }
 
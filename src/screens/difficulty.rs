@@ -2,27 +2,42 @@
 
 use bevy::prelude::*;
 
-use crate::{screens::Screen, theme::prelude::*};
+use crate::{
+    game::difficulty::Difficulty, localization::Localization, screens::Screen, theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Difficulty), spawn_difficulty_screen);
 }
 
-fn spawn_difficulty_screen(mut commands: Commands) {
+fn spawn_difficulty_screen(mut commands: Commands, localization: Res<Localization>) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Difficulty))
         .with_children(|children| {
-            children.button("Story").observe(enter_gameplay_screen);
-            children.button("Medium").observe(enter_gameplay_screen);
-            children.button("Brutal").observe(enter_gameplay_screen);
+            for difficulty in Difficulty::ALL {
+                children
+                    .button(localization.get(&difficulty.to_string()))
+                    .insert(difficulty)
+                    .observe(enter_gameplay_screen);
+            }
 
             children.label("");
-            children.button("Back").observe(enter_title_screen);
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
         });
 }
 
-fn enter_gameplay_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+fn enter_gameplay_screen(
+    trigger: Trigger<OnPress>,
+    mut commands: Commands,
+    difficulties: Query<&Difficulty>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if let Ok(difficulty) = difficulties.get(trigger.entity()) {
+        commands.insert_resource(*difficulty);
+    }
     next_screen.set(Screen::Gameplay);
 }
 
@@ -1,27 +1,93 @@
 //! A end screen that is shown after the game ends.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
 
-use crate::{asset_tracking::LoadResource, audio::Music, screens::Screen, theme::prelude::*};
+use crate::{
+    audio::Music,
+    game::{
+        difficulty::Difficulty,
+        ending::{Ending, EndingAssets, ReachedEnding, UnlockedEndings},
+        stats::RunStats,
+    },
+    localization::Localization,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::End), spawn_end_screen);
-
-    app.load_resource::<EndMusic>();
-    app.add_systems(OnEnter(Screen::End), play_end_music);
+    app.init_resource::<EndMusic>();
+    app.add_systems(OnEnter(Screen::End), (spawn_end_screen, play_end_music));
     app.add_systems(OnExit(Screen::End), stop_music);
 }
 
-fn spawn_end_screen(mut commands: Commands) {
+fn spawn_end_screen(
+    mut commands: Commands,
+    reached: Res<ReachedEnding>,
+    unlocked: Res<UnlockedEndings>,
+    ending_assets: Res<EndingAssets>,
+    stats: Res<RunStats>,
+    difficulty: Res<Difficulty>,
+    localization: Res<Localization>,
+) {
+    let ending = reached.0.unwrap_or(Ending::Good);
+    let info = ending.info();
+    let time = stats.time.as_secs();
     commands
         .ui_root()
         .insert(StateScoped(Screen::End))
         .with_children(|children| {
-            children.header("Mission passed!");
-            children.big_label("Respect+");
-            children.label("");
+            children.header(localization.get(info.title));
+            children.spawn((
+                Name::new("Ending Art"),
+                ImageBundle {
+                    image: UiImage::new(ending_assets.art(ending)),
+                    style: Style {
+                        height: Px(150.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+            children.big_label(localization.get(info.text));
+            children.big_label(format!(
+                "{}: {}",
+                localization.get("Respect"),
+                stats.respect(ending, *difficulty)
+            ));
+            children.label(format!(
+                "{}: {}\n\
+                 {}: {}:{:02}\n\
+                 {}: {:.0}\n\
+                 {}: {}\n\
+                 {}: {}\n\
+                 {}: {}\n\
+                 {}: {}",
+                localization.get("Difficulty"),
+                localization.get(&difficulty.to_string()),
+                localization.get("Time"),
+                time / 60,
+                time % 60,
+                localization.get("Distance walked"),
+                stats.distance,
+                localization.get("Items picked up"),
+                stats.items_picked_up.len(),
+                localization.get("Dialogues"),
+                stats.dialogues,
+                localization.get("Asked wife"),
+                stats.wife_asked,
+                localization.get("Area changes"),
+                stats.area_transitions,
+            ));
+            children.label(format!(
+                "{}: {}/{}",
+                localization.get("Endings found"),
+                unlocked.endings.len(),
+                Ending::ALL.len()
+            ));
 
-            children.button("Back").observe(enter_title_screen);
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
         });
 }
 
@@ -29,29 +95,23 @@ fn enter_title_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextSt
     next_screen.set(Screen::Title);
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
-pub struct EndMusic {
-    #[dependency]
-    music: Handle<AudioSource>,
+#[derive(Resource, Default)]
+struct EndMusic {
     entity: Option<Entity>,
 }
 
-impl FromWorld for EndMusic {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/sound_effects/end.ogg"),
-            entity: None,
-        }
-    }
-}
-
-fn play_end_music(mut commands: Commands, mut music: ResMut<EndMusic>) {
+fn play_end_music(
+    mut commands: Commands,
+    mut music: ResMut<EndMusic>,
+    reached: Res<ReachedEnding>,
+    ending_assets: Res<EndingAssets>,
+) {
+    let ending = reached.0.unwrap_or(Ending::Good);
     music.entity = Some(
         commands
             .spawn((
                 AudioBundle {
-                    source: music.music.clone(),
+                    source: ending_assets.music(ending),
                     settings: PlaybackSettings::ONCE,
                 },
                 Music,
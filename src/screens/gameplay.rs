@@ -2,12 +2,17 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::Music, screens::Screen};
+use crate::{
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    audio::Music,
+    screens::Screen,
+};
 
 use super::Area;
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<GameplayMusic>();
+    app.load_collection::<GameplayMusic>();
     app.add_systems(
         OnEnter(Area::Cave),
         (stop_ambience, play_cave_ambience).chain(),
@@ -19,23 +24,22 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnExit(Screen::Gameplay), stop_ambience);
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct GameplayMusic {
-    #[dependency]
     cave_handle: Handle<AudioSource>,
-    #[dependency]
     outside_handle: Handle<AudioSource>,
     entity: Option<Entity>,
 }
 
-impl FromWorld for GameplayMusic {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            cave_handle: assets.load("audio/music/cave.ogg"),
-            outside_handle: assets.load("audio/music/outside.ogg"),
+impl FromCollection for GameplayMusic {
+    const COLLECTION: &'static str = "gameplay";
+
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            cave_handle: collection.audio("cave")?,
+            outside_handle: collection.audio("outside")?,
             entity: None,
-        }
+        })
     }
 }
 
@@ -1,12 +1,15 @@
 //! A loading screen during which game assets are loaded.
 //! This reduces stuttering, especially for audio on WASM.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
+use bevy_yarnspinner::prelude::YarnProject;
 
 use crate::{
-    game::{fire::FireAssets, level::LevelAssets, player::PlayerAssets},
-    screens::{credits::CreditsMusic, gameplay::GameplayMusic, Screen},
-    theme::{interaction::InteractionAssets, prelude::*},
+    asset_tracking::{LoadingProgress, ResourceHandles},
+    localization::{Language, LanguageSettings, Localization},
+    replay::ReplayPlayer,
+    screens::Screen,
+    theme::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -14,38 +17,170 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        continue_to_title_screen.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        (
+            update_loading_text.run_if(resource_changed::<Localization>),
+            update_progress_bar.run_if(resource_changed::<LoadingProgress>),
+            show_failed_assets.run_if(resource_changed::<LoadingProgress>.and_then(loading_failed)),
+            // The dialogue is compiled only after its files are loaded, and
+            // replays need it as soon as they enter gameplay.
+            continue_to_title_screen
+                .run_if(all_assets_loaded.and_then(resource_exists::<YarnProject>)),
+        )
+            .chain()
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
-fn spawn_loading_screen(mut commands: Commands) {
+const PROGRESS_BAR_WIDTH: f32 = 500.0;
+
+#[derive(Component)]
+struct LoadingRoot;
+
+#[derive(Component)]
+struct LoadingText;
+
+#[derive(Component)]
+struct ProgressBarFill;
+
+/// "Loading..." in the selected language. The string tables are loaded on
+/// this screen too, so until the [`Localization`] is built for the language
+/// the text comes from here.
+fn loading_text<'a>(localization: &'a Localization, settings: &LanguageSettings) -> &'a str {
+    if localization.language() == settings.language {
+        return localization.get("Loading...");
+    }
+    match settings.language {
+        Language::English => "Loading...",
+        Language::German => "Laden...",
+    }
+}
+
+fn spawn_loading_screen(
+    mut commands: Commands,
+    localization: Res<Localization>,
+    settings: Res<LanguageSettings>,
+) {
     commands
         .ui_root()
-        .insert(StateScoped(Screen::Loading))
+        .insert((LoadingRoot, StateScoped(Screen::Loading)))
         .with_children(|children| {
-            children.label("Loading...").insert(Style {
-                justify_content: JustifyContent::Center,
-                ..default()
-            });
+            children
+                .label(loading_text(&localization, &settings))
+                .insert((
+                    LoadingText,
+                    Style {
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                ));
+            children
+                .spawn((
+                    Name::new("Progress Bar"),
+                    NodeBundle {
+                        style: Style {
+                            width: Px(PROGRESS_BAR_WIDTH),
+                            height: Px(20.0),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(ui_palette::PROGRESS_BAR_BACKGROUND),
+                        ..default()
+                    },
+                ))
+                .with_children(|children| {
+                    children.spawn((
+                        Name::new("Progress Bar Fill"),
+                        ProgressBarFill,
+                        NodeBundle {
+                            style: Style {
+                                width: Percent(0.0),
+                                height: Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(ui_palette::PROGRESS_BAR_FILL),
+                            ..default()
+                        },
+                    ));
+                });
         });
 }
 
-fn continue_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Title);
-}
-
-fn all_assets_loaded(
-    player_assets: Option<Res<PlayerAssets>>,
-    level_assets: Option<Res<LevelAssets>>,
-    fire_assets: Option<Res<FireAssets>>,
-    interaction_assets: Option<Res<InteractionAssets>>,
-    credits_music: Option<Res<CreditsMusic>>,
-    gameplay_music: Option<Res<GameplayMusic>>,
-) -> bool {
-    player_assets.is_some()
-        && level_assets.is_some()
-        && fire_assets.is_some()
-        && interaction_assets.is_some()
-        && credits_music.is_some()
-        && gameplay_music.is_some()
+fn update_loading_text(
+    localization: Res<Localization>,
+    settings: Res<LanguageSettings>,
+    mut texts: Query<&mut Text, With<LoadingText>>,
+) {
+    for mut text in &mut texts {
+        text.sections[0].value = loading_text(&localization, &settings).to_string();
+    }
+}
+
+fn update_progress_bar(
+    progress: Res<LoadingProgress>,
+    mut fill: Query<&mut Style, With<ProgressBarFill>>,
+) {
+    for mut style in &mut fill {
+        style.width = Percent(progress.fraction() * 100.0);
+    }
+}
+
+fn loading_failed(progress: Res<LoadingProgress>) -> bool {
+    !progress.failed.is_empty()
+}
+
+/// Replace the loading screen with the assets that failed, as the game would
+/// otherwise wait for them forever.
+fn show_failed_assets(
+    mut commands: Commands,
+    progress: Res<LoadingProgress>,
+    root: Query<Entity, With<LoadingRoot>>,
+    replay: Option<Res<ReplayPlayer>>,
+    localization: Res<Localization>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    for path in &progress.failed {
+        error!("failed to load {path}");
+    }
+    // There is nobody to read the error screen when checking a replay.
+    if replay.is_some_and(|replay| replay.headless()) {
+        app_exit.send(AppExit::error());
+        return;
+    }
+
+    let Ok(root) = root.get_single() else {
+        return;
+    };
+    commands
+        .entity(root)
+        .despawn_descendants()
+        .with_children(|children| {
+            children.header(localization.get("Loading failed"));
+            children.label(localization.get("These files could not be loaded:"));
+            for path in &progress.failed {
+                children.label(path);
+            }
+
+            #[cfg(not(target_family = "wasm"))]
+            children.button(localization.get("Exit")).observe(exit_app);
+        });
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn exit_app(_trigger: Trigger<OnPress>, mut app_exit: EventWriter<AppExit>) {
+    app_exit.send(AppExit::error());
+}
+
+fn continue_to_title_screen(
+    mut next_screen: ResMut<NextState<Screen>>,
+    replay: Option<Res<ReplayPlayer>>,
+) {
+    // Replays start right at the gameplay they recorded.
+    if replay.is_some() {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Title);
+    }
+}
+
+fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
+    resource_handles.is_all_done()
 }
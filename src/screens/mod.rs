@@ -1,15 +1,20 @@
 //! The game's main screen states and transitions between them.
 
-mod credits;
+mod achievements;
+pub mod credits;
 mod difficulty;
 mod end;
-mod gameplay;
+pub mod gameplay;
 mod loading;
+#[cfg(not(target_family = "wasm"))]
+mod mods;
+mod settings;
 mod splash;
 mod title;
 
 use bevy::prelude::*;
 use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
@@ -25,7 +30,11 @@ pub(super) fn plugin(app: &mut App) {
         title::plugin,
         difficulty::plugin,
         end::plugin,
+        settings::plugin,
+        achievements::plugin,
     ));
+    #[cfg(not(target_family = "wasm"))]
+    app.add_plugins(mods::plugin);
 }
 
 /// The game's main screen states.
@@ -38,11 +47,16 @@ pub enum Screen {
     Title,
     Difficulty,
     Credits,
+    Settings,
+    Achievements,
+    Mods,
     Gameplay,
     End,
 }
 
-#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Copy, Default, Display)]
+#[derive(
+    SubStates, Debug, Hash, PartialEq, Eq, Clone, Copy, Default, Display, Serialize, Deserialize,
+)]
 #[source(Screen = Screen::Gameplay)]
 pub enum Area {
     Cave,
@@ -0,0 +1,59 @@
+//! A screen listing the installed mods in load order, and their problems.
+
+use bevy::prelude::*;
+
+use crate::{
+    localization::Localization,
+    mods::{mods_path, Mods},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Mods), spawn_mods_screen);
+}
+
+fn spawn_mods_screen(mut commands: Commands, mods: Res<Mods>, localization: Res<Localization>) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Mods))
+        .with_children(|children| {
+            children.header(localization.get("Mods"));
+            if mods.mods.is_empty() {
+                children.label(format!(
+                    "{} {}",
+                    localization.get("No mods installed in"),
+                    mods_path().display()
+                ));
+            }
+            for (index, info) in mods.mods.iter().enumerate() {
+                if info.description.is_empty() {
+                    children.label(format!("{}. {}", index + 1, info.name));
+                } else {
+                    children.label(format!(
+                        "{}. {} - {}",
+                        index + 1,
+                        info.name,
+                        info.description
+                    ));
+                }
+            }
+            if !mods.problems.is_empty() {
+                children.label("");
+                children.label(localization.get("Problems:"));
+                for problem in &mods.problems {
+                    children.label(problem);
+                }
+            }
+
+            children.label("");
+            children.label(localization.get("Changes take effect after restarting the game."));
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
+        });
+}
+
+fn enter_title_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
@@ -0,0 +1,150 @@
+//! A settings screen that can be accessed from the title screen.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{ghost::GhostSettings, paper::PlayerName, speedrun::SpeedrunSettings},
+    localization::{LanguageSettings, Localization},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), spawn_settings_screen);
+    // Show the whole screen in the new language after switching.
+    app.add_systems(
+        Update,
+        (despawn_settings_screen, spawn_settings_screen)
+            .chain()
+            .run_if(resource_changed::<Localization>.and_then(in_state(Screen::Settings))),
+    );
+    app.add_systems(
+        Update,
+        update_player_name.run_if(in_state(Screen::Settings)),
+    );
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+#[derive(Component)]
+struct PlayerNameInput;
+
+const PLAYER_NAME_LENGTH: usize = 20;
+
+fn spawn_settings_screen(
+    mut commands: Commands,
+    speedrun_settings: Res<SpeedrunSettings>,
+    ghost_settings: Res<GhostSettings>,
+    player_name: Res<PlayerName>,
+    localization: Res<Localization>,
+) {
+    let mut name_input = TextInput::new(PLAYER_NAME_LENGTH, 1);
+    name_input.set_value(player_name.name.clone());
+    commands
+        .ui_root()
+        .insert((SettingsRoot, StateScoped(Screen::Settings)))
+        .with_children(|children| {
+            children.header(localization.get("Settings"));
+            children
+                .button(toggle_label(
+                    &localization,
+                    "Timer",
+                    speedrun_settings.enabled,
+                ))
+                .observe(toggle_speedrun_timer);
+            children
+                .button(toggle_label(&localization, "Ghost", ghost_settings.enabled))
+                .observe(toggle_ghost);
+            children
+                .button(format!(
+                    "{}: {}",
+                    localization.get("Language"),
+                    localization.language()
+                ))
+                .observe(switch_language);
+            children.label(localization.get("Name"));
+            children
+                .text_input(PLAYER_NAME_LENGTH, 1)
+                .insert((PlayerNameInput, name_input));
+
+            children.label("");
+            children
+                .button(localization.get("Back"))
+                .observe(enter_title_screen);
+        });
+}
+
+fn despawn_settings_screen(mut commands: Commands, root: Query<Entity, With<SettingsRoot>>) {
+    for entity in &root {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn enter_title_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn toggle_label(localization: &Localization, name: &str, enabled: bool) -> String {
+    format!(
+        "{}: {}",
+        localization.get(name),
+        localization.get(if enabled { "On" } else { "Off" })
+    )
+}
+
+/// Replace the text of a button spawned with `Widgets::button`.
+fn set_button_text(
+    button: Entity,
+    text: String,
+    children: &Query<&Children>,
+    texts: &mut Query<&mut Text>,
+) {
+    for child in children.iter_descendants(button) {
+        if let Ok(mut button_text) = texts.get_mut(child) {
+            button_text.sections[0].value.clone_from(&text);
+        }
+    }
+}
+
+fn toggle_speedrun_timer(
+    trigger: Trigger<OnPress>,
+    mut speedrun_settings: ResMut<SpeedrunSettings>,
+    localization: Res<Localization>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    speedrun_settings.enabled = !speedrun_settings.enabled;
+    let label = toggle_label(&localization, "Timer", speedrun_settings.enabled);
+    set_button_text(trigger.entity(), label, &children, &mut texts);
+}
+
+fn toggle_ghost(
+    trigger: Trigger<OnPress>,
+    mut ghost_settings: ResMut<GhostSettings>,
+    localization: Res<Localization>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    ghost_settings.enabled = !ghost_settings.enabled;
+    let label = toggle_label(&localization, "Ghost", ghost_settings.enabled);
+    set_button_text(trigger.entity(), label, &children, &mut texts);
+}
+
+fn update_player_name(
+    input: Query<&TextInput, With<PlayerNameInput>>,
+    mut player_name: ResMut<PlayerName>,
+) {
+    for input in &input {
+        // Only save when the name changed, not when the caret blinks.
+        if player_name.name != input.value() {
+            player_name.name = input.value().to_string();
+        }
+    }
+}
+
+/// Languages are shown by their own name, so switching cycles through them
+/// and the screen is respawned once the new text is in place.
+fn switch_language(_trigger: Trigger<OnPress>, mut language_settings: ResMut<LanguageSettings>) {
+    language_settings.language = language_settings.language.next();
+}
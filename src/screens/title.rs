@@ -2,22 +2,36 @@
 
 use bevy::prelude::*;
 
-use crate::{screens::Screen, theme::prelude::*};
+use crate::{localization::Localization, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), spawn_title_screen);
 }
 
-fn spawn_title_screen(mut commands: Commands) {
+fn spawn_title_screen(mut commands: Commands, localization: Res<Localization>) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
-            children.button("Play").observe(enter_difficulty_screen);
-            children.button("Credits").observe(enter_credits_screen);
+            children
+                .button(localization.get("Play"))
+                .observe(enter_difficulty_screen);
+            children
+                .button(localization.get("Settings"))
+                .observe(enter_settings_screen);
+            children
+                .button(localization.get("Achievements"))
+                .observe(enter_achievements_screen);
+            #[cfg(not(target_family = "wasm"))]
+            children
+                .button(localization.get("Mods"))
+                .observe(enter_mods_screen);
+            children
+                .button(localization.get("Credits"))
+                .observe(enter_credits_screen);
 
             #[cfg(not(target_family = "wasm"))]
-            children.button("Exit").observe(exit_app);
+            children.button(localization.get("Exit")).observe(exit_app);
         });
 }
 
@@ -25,6 +39,22 @@ fn enter_difficulty_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<N
     next_screen.set(Screen::Difficulty);
 }
 
+fn enter_settings_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Settings);
+}
+
+fn enter_achievements_screen(
+    _trigger: Trigger<OnPress>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Achievements);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn enter_mods_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Mods);
+}
+
 fn enter_credits_screen(_trigger: Trigger<OnPress>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Credits);
 }
@@ -0,0 +1,20 @@
+//! The font of the UI.
+//!
+//! Bevy's default font only covers ASCII, which isn't enough for the
+//! translations. Fira Sans covers Latin-1 and more, see
+//! `assets/fonts/FiraSans-LICENSE.txt` for its license. It is embedded, so
+//! that widgets can use it without access to the assets.
+
+use bevy::{asset::load_internal_binary_asset, prelude::*};
+
+pub const UI_FONT: Handle<Font> = Handle::weak_from_u128(0x6c1f_4e0b_2a8d_4a7e_9b3c_5d1e_8f20_7a64);
+
+pub(super) fn plugin(app: &mut App) {
+    load_internal_binary_asset!(
+        app,
+        UI_FONT,
+        "../../assets/fonts/FiraSans-Regular.ttf",
+        |bytes: &[u8], _path: String| Font::try_from_bytes(bytes.to_vec())
+            .expect("the UI font should be a valid font")
+    );
+}
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::SoundEffect};
+use crate::{
+    asset_manifest::AssetCollection,
+    asset_tracking::{FromCollection, LoadResource},
+    audio::SoundEffect,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<InteractionPalette>();
-    app.load_resource::<InteractionAssets>();
+    app.load_collection::<InteractionAssets>();
     app.add_systems(
         Update,
         (
@@ -59,26 +63,20 @@ fn apply_interaction_palette(
     }
 }
 
-#[derive(Resource, Asset, Reflect, Clone)]
+#[derive(Resource, Reflect, Clone)]
 pub struct InteractionAssets {
-    #[dependency]
     hover: Handle<AudioSource>,
-    #[dependency]
     press: Handle<AudioSource>,
 }
 
-impl InteractionAssets {
-    pub const PATH_BUTTON_HOVER: &'static str = "audio/sound_effects/button_hover.ogg";
-    pub const PATH_BUTTON_PRESS: &'static str = "audio/sound_effects/button_press.ogg";
-}
+impl FromCollection for InteractionAssets {
+    const COLLECTION: &'static str = "interaction";
 
-impl FromWorld for InteractionAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            hover: assets.load(Self::PATH_BUTTON_HOVER),
-            press: assets.load(Self::PATH_BUTTON_PRESS),
-        }
+    fn from_collection(collection: &AssetCollection) -> Result<Self, String> {
+        Ok(Self {
+            hover: collection.audio("button_hover")?,
+            press: collection.audio("button_press")?,
+        })
     }
 }
 
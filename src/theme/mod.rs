@@ -3,15 +3,21 @@
 // Unused utilities may trigger this lints undesirably.
 #![allow(dead_code)]
 
+pub mod font;
 pub mod interaction;
 pub mod palette;
+pub mod text_input;
+pub mod toast;
 mod widgets;
 
 #[allow(unused_imports)]
 pub mod prelude {
     pub use super::{
+        font::UI_FONT,
         interaction::{InteractionPalette, OnPress},
         palette as ui_palette,
+        text_input::{OnTextConfirm, TextInput},
+        toast::Toast,
         widgets::{Containers as _, Widgets as _},
     };
 }
@@ -19,5 +25,10 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((
+        font::plugin,
+        interaction::plugin,
+        text_input::plugin,
+        toast::plugin,
+    ));
 }
@@ -12,3 +12,9 @@ pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 pub const NODE_BACKGROUND: Color = Color::srgb(0.4, 0.239, 0.145);
 pub const ITEM_NODE_BACKGROUND: Color = Color::srgb(0.300, 0.3, 0.25);
+
+pub const PROGRESS_BAR_BACKGROUND: Color = Color::srgb(0.4, 0.239, 0.145);
+pub const PROGRESS_BAR_FILL: Color = Color::srgb(0.867, 0.827, 0.412);
+
+pub const BUBBLE_BACKGROUND: Color = Color::srgba(0.925, 0.925, 0.925, 0.9);
+pub const BUBBLE_TEXT: Color = Color::srgb(0.1, 0.1, 0.1);
@@ -0,0 +1,242 @@
+//! An editable, word wrapped text field that works for both UI text and
+//! [`Text2dBundle`]s.
+
+use std::time::Duration;
+
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TextInput>();
+    app.add_systems(
+        Update,
+        (edit_text_input, blink_caret, render_text_input).chain(),
+    );
+}
+
+/// Turns the [`Text`] on the same entity into an editable text field.
+/// The style of the first text section is used for the typed text.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TextInput {
+    value: String,
+    /// Cursor position as a char index into `value`.
+    cursor: usize,
+    /// Maximum number of characters per line before the text wraps.
+    pub line_width: usize,
+    pub max_lines: usize,
+    /// Whether keyboard input goes to this text field.
+    pub focused: bool,
+    caret_timer: Timer,
+    caret_visible: bool,
+}
+
+/// Event triggered on a [`TextInput`] entity when enter is pressed.
+#[derive(Event, Debug)]
+pub struct OnTextConfirm;
+
+const CARET_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+impl TextInput {
+    pub fn new(line_width: usize, max_lines: usize) -> Self {
+        Self {
+            value: String::new(),
+            cursor: 0,
+            line_width,
+            max_lines,
+            focused: true,
+            caret_timer: Timer::new(CARET_BLINK_INTERVAL, TimerMode::Repeating),
+            caret_visible: true,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+    }
+
+    fn insert(&mut self, character: char) {
+        let mut chars = self.value.chars().collect::<Vec<_>>();
+        chars.insert(self.cursor, character);
+        if line_starts(&chars, self.line_width).len() > self.max_lines {
+            return;
+        }
+        self.value = chars.into_iter().collect();
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.cursor -= 1;
+        self.delete();
+    }
+
+    fn delete(&mut self) {
+        let mut chars = self.value.chars().collect::<Vec<_>>();
+        if self.cursor < chars.len() {
+            chars.remove(self.cursor);
+            self.value = chars.into_iter().collect();
+        }
+    }
+
+    /// Move the cursor up or down by the given number of wrapped lines,
+    /// keeping its column where possible.
+    fn move_lines(&mut self, lines: isize) {
+        let chars = self.value.chars().collect::<Vec<_>>();
+        let starts = line_starts(&chars, self.line_width);
+        let line = starts
+            .iter()
+            .rposition(|start| *start <= self.cursor)
+            .unwrap();
+        let column = self.cursor - starts[line];
+
+        let Some(target) = line
+            .checked_add_signed(lines)
+            .filter(|target| *target < starts.len())
+        else {
+            return;
+        };
+        let target_start = starts[target];
+        let target_end = starts.get(target + 1).copied().unwrap_or(chars.len());
+        self.cursor = (target_start + column).min(target_end);
+    }
+
+    /// The displayed text before and after the caret, with line breaks.
+    fn display(&self) -> (String, String) {
+        let chars = self.value.chars().collect::<Vec<_>>();
+        let starts = line_starts(&chars, self.line_width);
+        let mut before = String::new();
+        let mut after = String::new();
+        for (index, character) in chars.into_iter().enumerate() {
+            // Show the caret at the start of a wrapped line rather than at the
+            // end of the previous one.
+            if index > 0 && starts.contains(&index) {
+                if index <= self.cursor {
+                    before.push('\n');
+                } else {
+                    after.push('\n');
+                }
+            }
+            if index < self.cursor {
+                before.push(character);
+            } else {
+                after.push(character);
+            }
+        }
+        (before, after)
+    }
+}
+
+/// Split text into lines of at most `width` characters, breaking after spaces
+/// where possible. Returns the char index each line starts at.
+fn line_starts(chars: &[char], width: usize) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut start = 0;
+    while chars.len() - start > width {
+        let limit = start + width;
+        let end = chars[start..limit]
+            .iter()
+            .rposition(|character| *character == ' ')
+            .map(|space| start + space + 1)
+            .unwrap_or(limit);
+        starts.push(end);
+        start = end;
+    }
+    starts
+}
+
+fn edit_text_input(
+    mut commands: Commands,
+    mut events: EventReader<KeyboardInput>,
+    mut inputs: Query<(Entity, &mut TextInput)>,
+) {
+    let events = events
+        .read()
+        // Only trigger changes when the key is pressed or repeated.
+        .filter(|event| event.state.is_pressed())
+        .collect::<Vec<_>>();
+    for (entity, mut input) in &mut inputs {
+        if !input.focused {
+            continue;
+        }
+        for event in &events {
+            match &event.logical_key {
+                Key::Character(characters) => {
+                    for character in characters.chars().filter(|c| !c.is_control()) {
+                        input.insert(character);
+                    }
+                }
+                Key::Space => input.insert(' '),
+                Key::Backspace => input.backspace(),
+                Key::Delete => input.delete(),
+                Key::ArrowLeft => input.cursor = input.cursor.saturating_sub(1),
+                Key::ArrowRight => {
+                    input.cursor = (input.cursor + 1).min(input.value.chars().count())
+                }
+                Key::ArrowUp => input.move_lines(-1),
+                Key::ArrowDown => input.move_lines(1),
+                Key::Home => input.cursor = 0,
+                Key::End => input.cursor = input.value.chars().count(),
+                Key::Enter => commands.trigger_targets(OnTextConfirm, entity),
+                _ => continue,
+            }
+            // Keep the caret visible while typing.
+            input.caret_visible = true;
+            input.caret_timer.reset();
+        }
+    }
+}
+
+fn blink_caret(time: Res<Time>, mut inputs: Query<&mut TextInput>) {
+    for mut input in &mut inputs {
+        // Only mark the input as changed when the caret actually blinks.
+        let blink = input
+            .bypass_change_detection()
+            .caret_timer
+            .tick(time.delta())
+            .just_finished();
+        if blink {
+            input.caret_visible = !input.caret_visible;
+        }
+    }
+}
+
+fn render_text_input(mut inputs: Query<(&TextInput, &mut Text), Changed<TextInput>>) {
+    for (input, mut text) in &mut inputs {
+        let style = text.sections[0].style.clone();
+        if text.sections.len() != 3 {
+            text.sections = vec![
+                TextSection::new("", style.clone()),
+                // The caret uses the default font, as not every font has a glyph for it.
+                TextSection::new(
+                    "|",
+                    TextStyle {
+                        font_size: style.font_size,
+                        color: style.color,
+                        ..default()
+                    },
+                ),
+                TextSection::new("", style),
+            ];
+        }
+
+        let (before, after) = input.display();
+        text.sections[0].value = before;
+        text.sections[2].value = after;
+
+        let caret_alpha = if input.focused && input.caret_visible {
+            1.0
+        } else {
+            0.0
+        };
+        text.sections[1].style.color.set_alpha(caret_alpha);
+    }
+}
@@ -0,0 +1,209 @@
+//! Short notifications that slide in at the top right of the screen, like
+//! "+ Knife" when an item is picked up. Toasts are queued and only a few are
+//! shown at once, stacked below each other.
+//!
+//! Trigger a [`Toast`] from Rust, or use `<<toast "Text">>` in Yarn.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, ui::Val::*};
+use bevy_tweening::{lens::UiPositionLens, Animator, Delay, EaseFunction, Tween, TweenCompleted};
+
+use super::{font::UI_FONT, palette::*};
+use crate::localization::Localization;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ToastQueue>();
+    app.add_systems(Startup, spawn_toast_stack);
+    app.add_systems(Update, (despawn_hidden_toasts, show_queued_toasts).chain());
+    app.observe(queue_toast);
+}
+
+/// Event to show a toast. The text is written in English and translated when
+/// it is shown, text that is not in the string tables is shown as is.
+#[derive(Event, Debug, Clone)]
+pub struct Toast {
+    pub text: String,
+    pub icon: Option<Handle<Image>>,
+    /// The part of the icon to show, if it is a sprite sheet.
+    pub atlas: Option<TextureAtlas>,
+}
+
+impl Toast {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            icon: None,
+            atlas: None,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Handle<Image>) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_atlas(mut self, atlas: TextureAtlas) -> Self {
+        self.atlas = Some(atlas);
+        self
+    }
+}
+
+/// Toasts waiting for a free spot in the stack.
+#[derive(Resource, Debug, Default)]
+struct ToastQueue(VecDeque<Toast>);
+
+/// How many toasts are shown at once.
+const MAX_VISIBLE: usize = 3;
+/// How long a toast stays after sliding in.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+const SLIDE_DURATION: Duration = Duration::from_millis(300);
+const TOAST_WIDTH: f32 = 320.0;
+const ICON_SIZE: f32 = 40.0;
+/// User data of the [`TweenCompleted`] event sent once a toast slid out.
+const TOAST_HIDDEN: u64 = 1;
+
+/// The column toasts are stacked in. It isn't scoped to a screen, so toasts
+/// stay when the screen changes, e.g. for an achievement unlocked by an
+/// ending.
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct ToastNode;
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Toast Stack"),
+        ToastStack,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Px(80.0),
+                right: Px(10.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Px(8.0),
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+    ));
+}
+
+fn queue_toast(trigger: Trigger<Toast>, mut queue: ResMut<ToastQueue>) {
+    queue.0.push_back(trigger.event().clone());
+}
+
+fn show_queued_toasts(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    stack: Query<(Entity, Option<&Children>), With<ToastStack>>,
+    localization: Res<Localization>,
+) {
+    let Ok((stack, children)) = stack.get_single() else {
+        return;
+    };
+    let mut visible = children.map_or(0, |children| children.len());
+    while visible < MAX_VISIBLE {
+        let Some(toast) = queue.0.pop_front() else {
+            return;
+        };
+        let text = localization.get(&toast.text).to_string();
+        commands.entity(stack).with_children(|children| {
+            spawn_toast(children, toast, text);
+        });
+        visible += 1;
+    }
+}
+
+fn spawn_toast(children: &mut ChildBuilder, toast: Toast, text: String) {
+    let hidden = UiRect::left(Px(TOAST_WIDTH + 20.0));
+    let shown = UiRect::left(Px(0.0));
+    let tween = Tween::new(
+        EaseFunction::QuadraticOut,
+        SLIDE_DURATION,
+        UiPositionLens {
+            start: hidden,
+            end: shown,
+        },
+    )
+    .then(Delay::new(TOAST_DURATION))
+    .then(
+        Tween::new(
+            EaseFunction::QuadraticIn,
+            SLIDE_DURATION,
+            UiPositionLens {
+                start: shown,
+                end: hidden,
+            },
+        )
+        .with_completed_event(TOAST_HIDDEN),
+    );
+
+    children
+        .spawn((
+            Name::new("Toast"),
+            ToastNode,
+            NodeBundle {
+                style: Style {
+                    width: Px(TOAST_WIDTH),
+                    left: hidden.left,
+                    align_items: AlignItems::Center,
+                    column_gap: Px(10.0),
+                    padding: UiRect::all(Px(8.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(NODE_BACKGROUND),
+                ..default()
+            },
+            Animator::new(tween),
+        ))
+        .with_children(|children| {
+            if let Some(icon) = toast.icon {
+                let mut entity = children.spawn((
+                    Name::new("Toast Icon"),
+                    ImageBundle {
+                        style: Style {
+                            width: Px(ICON_SIZE),
+                            height: Px(ICON_SIZE),
+                            ..default()
+                        },
+                        image: UiImage::new(icon),
+                        ..default()
+                    },
+                ));
+                if let Some(atlas) = toast.atlas {
+                    entity.insert(atlas);
+                }
+            }
+            children.spawn((
+                Name::new("Toast Text"),
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font: UI_FONT,
+                        font_size: 24.0,
+                        color: LABEL_TEXT,
+                    },
+                )
+                .with_style(Style {
+                    flex_shrink: 1.0,
+                    ..default()
+                }),
+            ));
+        });
+}
+
+fn despawn_hidden_toasts(
+    mut commands: Commands,
+    mut events: EventReader<TweenCompleted>,
+    toasts: Query<(), With<ToastNode>>,
+) {
+    for event in events.read() {
+        if event.user_data == TOAST_HIDDEN && toasts.contains(event.entity) {
+            commands.entity(event.entity).despawn_recursive();
+        }
+    }
+}
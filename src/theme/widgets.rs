@@ -2,7 +2,9 @@
 
 use bevy::{ecs::system::EntityCommands, prelude::*, ui::Val::*};
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{
+    font::UI_FONT, interaction::InteractionPalette, palette::*, text_input::TextInput,
+};
 
 /// An extension trait for spawning UI widgets.
 pub trait Widgets {
@@ -19,6 +21,9 @@ pub trait Widgets {
     fn label(&mut self, text: impl Into<String>) -> EntityCommands;
 
     fn big_label(&mut self, text: impl Into<String>) -> EntityCommands;
+
+    /// Spawn an editable text field, see [`TextInput`].
+    fn text_input(&mut self, line_width: usize, max_lines: usize) -> EntityCommands;
 }
 
 impl<T: Spawn> Widgets for T {
@@ -48,9 +53,9 @@ impl<T: Spawn> Widgets for T {
                 TextBundle::from_section(
                     text,
                     TextStyle {
+                        font: UI_FONT,
                         font_size: 40.0,
                         color: BUTTON_TEXT,
-                        ..default()
                     },
                 ),
             ));
@@ -118,9 +123,9 @@ impl<T: Spawn> Widgets for T {
                 TextBundle::from_section(
                     text,
                     TextStyle {
+                        font: UI_FONT,
                         font_size: 40.0,
                         color: HEADER_TEXT,
-                        ..default()
                     },
                 ),
             ));
@@ -134,9 +139,9 @@ impl<T: Spawn> Widgets for T {
             TextBundle::from_section(
                 text,
                 TextStyle {
+                    font: UI_FONT,
                     font_size: 24.0,
                     color: LABEL_TEXT,
-                    ..default()
                 },
             )
             .with_style(Style {
@@ -153,9 +158,9 @@ impl<T: Spawn> Widgets for T {
             TextBundle::from_section(
                 text,
                 TextStyle {
+                    font: UI_FONT,
                     font_size: 40.0,
                     color: LABEL_TEXT,
-                    ..default()
                 },
             )
             .with_text_justify(JustifyText::Center)
@@ -166,6 +171,28 @@ impl<T: Spawn> Widgets for T {
         ));
         entity
     }
+
+    fn text_input(&mut self, line_width: usize, max_lines: usize) -> EntityCommands {
+        let entity = self.spawn((
+            Name::new("Text Input"),
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: UI_FONT,
+                    font_size: 24.0,
+                    color: BUTTON_TEXT,
+                },
+            )
+            .with_style(Style {
+                width: Px(500.0),
+                padding: UiRect::all(Px(10.0)),
+                ..default()
+            })
+            .with_background_color(NODE_BACKGROUND),
+            TextInput::new(line_width, max_lines),
+        ));
+        entity
+    }
 }
 
 /// An extension trait for spawning UI containers.
@@ -0,0 +1,344 @@
+//! Checks that the dialogue and the assets fit the game, so that mistakes
+//! show up before they make the game panic halfway through a run.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    asset_manifest::{AssetCollection, ManifestFile, MANIFEST_PATH},
+    asset_tracking::FromCollection,
+    definitions::{discover_files, read_definitions, Definitions},
+    dialogue::{
+        discover_yarn_files, load_yarn_files, manifest_yarn_files, DIRECTIONS, SOUNDS,
+        STARTED_NODES,
+    },
+    game::{
+        achievements::Achievement,
+        dino::DinoAssets,
+        ending::{Ending, EndingAssets},
+        fire::FireAssets,
+        hints::HintStep,
+        inventory::ItemDefinitions,
+        journal::Objective,
+        level::{AreaLayouts, LevelAssets},
+        player::PlayerAssets,
+        recipe,
+        trigger::{TriggerAction, TriggerCondition},
+        wife::WifeAssets,
+    },
+    localization::{read_dialogue_strings, read_string_table, Language},
+    screens::{credits::CreditsMusic, gameplay::GameplayMusic},
+    theme::interaction::InteractionAssets,
+    yarn_script::{commands, dialogue_lines},
+};
+
+/// Check the content in `assets`, returning every problem found.
+pub fn validate(assets: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let mut paths = Vec::new();
+    match fs::read_to_string(assets.join(MANIFEST_PATH)) {
+        Ok(source) => match ron::from_str::<ManifestFile>(&source) {
+            Ok(manifest) => {
+                for collection in manifest.collections.values() {
+                    paths.extend(collection.paths().map(ToString::to_string));
+                }
+                check_collection::<CreditsMusic>(&manifest, &mut problems);
+                check_collection::<DinoAssets>(&manifest, &mut problems);
+                check_collection::<EndingAssets>(&manifest, &mut problems);
+                check_collection::<FireAssets>(&manifest, &mut problems);
+                check_collection::<GameplayMusic>(&manifest, &mut problems);
+                check_collection::<InteractionAssets>(&manifest, &mut problems);
+                check_collection::<LevelAssets>(&manifest, &mut problems);
+                check_collection::<PlayerAssets>(&manifest, &mut problems);
+                check_collection::<WifeAssets>(&manifest, &mut problems);
+            }
+            Err(error) => problems.push(format!("invalid {MANIFEST_PATH}: {error}")),
+        },
+        Err(error) => problems.push(format!("could not read {MANIFEST_PATH}: {error}")),
+    }
+    for path in paths {
+        if !assets.join(&path).is_file() {
+            problems.push(format!("missing asset {path}"));
+        }
+    }
+
+    // The web build only loads the files in the manifest.
+    match discover_yarn_files(assets) {
+        Ok(files) => {
+            let manifest = manifest_yarn_files();
+            for file in &files {
+                if !manifest.contains(file) {
+                    problems.push(format!("{file} is missing from dialogue/manifest.ron"));
+                }
+            }
+            for file in manifest {
+                if !files.contains(&file) {
+                    problems.push(format!("{file} is in dialogue/manifest.ron but missing"));
+                }
+            }
+        }
+        Err(error) => problems.push(format!("could not list dialogue files: {error}")),
+    }
+    let items = read_checked_definitions::<ItemDefinitions>(assets, &mut problems);
+    let layouts = read_checked_definitions::<AreaLayouts>(assets, &mut problems);
+    for definition in &items.items {
+        if !assets.join(&definition.image).is_file() {
+            problems.push(format!(
+                "missing image {} of item {}",
+                definition.image, definition.id
+            ));
+        }
+    }
+    check_localization(assets, &items, &mut problems);
+
+    // Found ducks are saved by id.
+    let mut duck_ids = Vec::new();
+    for (area, layout) in layouts.iter() {
+        for duck in &layout.ducks {
+            if duck_ids.contains(&duck.id) {
+                problems.push(format!("duck {} is placed twice", duck.id));
+            }
+            duck_ids.push(duck.id.clone());
+        }
+        let conditions = layout
+            .trigger_zones
+            .iter()
+            .flat_map(|zone| &zone.conditions)
+            .filter_map(|condition| match condition {
+                TriggerCondition::HasItem(item) | TriggerCondition::LacksItem(item) => Some(item),
+                TriggerCondition::YarnFlag(..) => None,
+            });
+        let placed = layout.items.iter().map(|placement| &placement.item);
+        for item in placed.chain(conditions) {
+            if items.get(item).is_none() {
+                problems.push(format!(
+                    "the layout of {area} refers to unknown item {item}"
+                ));
+            }
+        }
+    }
+
+    let program = match load_yarn_files(assets) {
+        Ok(program) => program,
+        Err(error) => {
+            problems.push(error);
+            return problems;
+        }
+    };
+
+    let mut started = STARTED_NODES
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    started.extend(recipe::started_nodes(&items));
+    for (_, layout) in layouts.iter() {
+        for zone in &layout.trigger_zones {
+            if let TriggerAction::StartNode(node) = &zone.action {
+                started.push(node.clone());
+            }
+        }
+    }
+    for node in started {
+        if !program.has_node(&node) {
+            problems.push(format!("node {node} is started by the game but missing"));
+        }
+    }
+
+    for file in discover_yarn_files(assets).unwrap_or_default() {
+        let Ok(source) = fs::read_to_string(assets.join(&file)) else {
+            continue;
+        };
+        for command in commands(&source) {
+            // Interpolated arguments are only known when the command runs.
+            if command.args.iter().any(|arg| arg.contains('{')) {
+                continue;
+            }
+            if let Err(error) = check_command(&command.name, &command.args, &items) {
+                problems.push(format!("{file}:{}: {error}", command.line));
+            }
+        }
+    }
+    // Variables that track the inventory have to name an item.
+    for variable in &program.variables {
+        let Some(item) = variable.name.strip_prefix("$_has_") else {
+            continue;
+        };
+        if items.find(item).is_none() {
+            problems.push(format!(
+                "{}: {} refers to unknown item {item}",
+                variable.file.as_deref().unwrap_or("dialogue"),
+                variable.name
+            ));
+        }
+    }
+    problems
+}
+
+/// Read the definitions in `assets`, and check that the web build, which
+/// can't list folders, loads the same files.
+fn read_checked_definitions<T: Definitions>(assets: &Path, problems: &mut Vec<String>) -> T {
+    match discover_files::<T>(assets) {
+        Ok(files) => {
+            for file in &files {
+                if !T::FILES.contains(&file.as_str()) {
+                    problems.push(format!("{file} is missing from the files of the web build"));
+                }
+            }
+            for file in T::FILES {
+                if !files.iter().any(|other| other == file) {
+                    problems.push(format!("{file} is loaded by the web build but missing"));
+                }
+            }
+        }
+        Err(error) => problems.push(format!("could not list {}: {error}", T::FOLDER)),
+    }
+    read_definitions(assets).unwrap_or_else(|error| {
+        problems.push(error);
+        T::default()
+    })
+}
+
+/// Check that every dialogue line can be translated, and that the string
+/// tables only translate text that still exists.
+fn check_localization(assets: &Path, items: &ItemDefinitions, problems: &mut Vec<String>) {
+    // Where every line id is used.
+    let mut ids = BTreeMap::new();
+    for file in discover_yarn_files(assets).unwrap_or_default() {
+        let Ok(source) = fs::read_to_string(assets.join(&file)) else {
+            continue;
+        };
+        for line in dialogue_lines(&source) {
+            let location = format!("{file}:{}", line.line);
+            let Some(id) = line.id else {
+                problems.push(format!("{location}: line has no #line: tag"));
+                continue;
+            };
+            if let Some(other) = ids.insert(id.clone(), location.clone()) {
+                problems.push(format!("{location}: {id} is already used in {other}"));
+            }
+        }
+    }
+
+    let english = match read_string_table(assets, Language::BASE) {
+        Ok(table) => table,
+        Err(error) => {
+            problems.push(error);
+            return;
+        }
+    };
+    // Text from code that can't be found by looking at the screens.
+    let texts = HintStep::ALL
+        .into_iter()
+        .flat_map(HintStep::hints)
+        .chain(HintStep::ALL.into_iter().map(HintStep::wife_hint))
+        .chain(Objective::ALL.into_iter().map(Objective::description))
+        .chain(Ending::ALL.into_iter().flat_map(|ending| {
+            let info = ending.info();
+            [info.title, info.text]
+        }))
+        .chain(
+            Achievement::ALL
+                .into_iter()
+                .flat_map(|achievement| [achievement.title(), achievement.description()]),
+        )
+        .map(ToString::to_string)
+        .chain(items.items.iter().map(|definition| definition.name.clone()));
+    for text in texts {
+        if !english.contains_key(&text) {
+            problems.push(format!(
+                "{text:?} is missing from {}",
+                Language::BASE.string_table_path()
+            ));
+        }
+    }
+    for language in Language::ALL {
+        if language == Language::BASE {
+            continue;
+        }
+        match read_string_table(assets, language) {
+            Ok(table) => {
+                for text in table.keys().filter(|text| !english.contains_key(*text)) {
+                    problems.push(format!(
+                        "{} translates {text:?}, which is missing from {}",
+                        language.string_table_path(),
+                        Language::BASE.string_table_path()
+                    ));
+                }
+            }
+            Err(error) => problems.push(error),
+        }
+        match read_dialogue_strings(assets, language) {
+            Ok(lines) => {
+                for id in lines.keys().filter(|id| !ids.contains_key(*id)) {
+                    problems.push(format!(
+                        "{} translates {id}, which no dialogue line has",
+                        language.dialogue_strings_path()
+                    ));
+                }
+            }
+            Err(error) => problems.push(error),
+        }
+    }
+}
+
+/// Check that the resource can be made from its collection in the manifest.
+fn check_collection<T: FromCollection>(manifest: &ManifestFile, problems: &mut Vec<String>) {
+    let Some(file) = manifest.collections.get(T::COLLECTION) else {
+        problems.push(format!(
+            "{MANIFEST_PATH} has no collection named {}",
+            T::COLLECTION
+        ));
+        return;
+    };
+    if let Err(error) = T::from_collection(&AssetCollection::unloaded(T::COLLECTION, file)) {
+        problems.push(error);
+    }
+}
+
+/// Check a command against what the functions registered in
+/// `spawn_dialogue_runner` accept.
+fn check_command(name: &str, args: &[String], items: &ItemDefinitions) -> Result<(), String> {
+    let item = |name: &String| {
+        items
+            .find(name)
+            .map(|_| ())
+            .ok_or(format!("unknown item {name}"))
+    };
+    let number = |text: &String| {
+        text.parse::<f32>()
+            .map(|_| ())
+            .map_err(|_| format!("{text} is not a number"))
+    };
+    match (name, args) {
+        ("inventory_convert" | "level_convert", [from, to]) => item(from).and(item(to)),
+        ("drop", [dropped]) => item(dropped),
+        ("spawn_dino", []) => Ok(()),
+        ("player_run", [direction, end_position]) => {
+            if !DIRECTIONS.contains(&direction.as_str()) {
+                return Err(format!("unknown direction {direction}"));
+            }
+            number(end_position)
+        }
+        ("play_sound", [sound]) if SOUNDS.contains(&sound.as_str()) => Ok(()),
+        ("play_sound", [sound]) => Err(format!("unknown sound {sound}")),
+        ("say", [_, _]) => Ok(()),
+        ("toast", [_]) => Ok(()),
+        ("end_game", [id]) => Ending::from_id(id)
+            .map(|_| ())
+            .ok_or(format!("unknown ending {id}")),
+        ("objective_add" | "objective_complete", [id]) => Objective::from_id(id)
+            .map(|_| ())
+            .ok_or(format!("unknown objective {id}")),
+        ("unlock_achievement", [id]) => Achievement::from_id(id)
+            .map(|_| ())
+            .ok_or(format!("unknown achievement {id}")),
+        ("wait", [duration]) => number(duration),
+        (
+            "inventory_convert" | "level_convert" | "drop" | "spawn_dino" | "player_run"
+            | "play_sound" | "say" | "toast" | "objective_add" | "objective_complete"
+            | "unlock_achievement" | "end_game" | "wait",
+            _,
+        ) => Err(format!("wrong number of arguments for {name}")),
+        _ => Err(format!("unknown command {name}")),
+    }
+}
@@ -0,0 +1,538 @@
+//! Compiling and running our dialogue outside of the game's dialogue runner,
+//! so that tools and the puzzle graph can reason about it.
+//!
+//! Yarn files are compiled by Yarn Spinner's compiler and nodes are run by
+//! its runtime, so the dialogue behaves exactly as it does in the game.
+//! Lines, commands and node titles can also be listed straight from the
+//! source, together with where they are.
+
+use std::collections::{BTreeMap, HashMap};
+
+use yarnspinner::{
+    compiler::{Compilation, Compiler, DeclarationSource, File},
+    core::{Library, LineId, Program as YarnProgram},
+    runtime::{
+        Dialogue, DialogueEvent, MemoryVariableStorage, StringTableTextProvider, VariableStorage,
+    },
+};
+
+pub use yarnspinner::core::YarnValue;
+
+/// A set of Yarn files, compiled.
+#[derive(Debug)]
+pub struct Program {
+    program: YarnProgram,
+    string_table: HashMap<LineId, String>,
+    /// Every variable the dialogue uses, declared or not.
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub initial_value: Option<YarnValue>,
+    /// The file the variable is first used in, if it isn't built into Yarn
+    /// Spinner.
+    pub file: Option<String>,
+}
+
+impl Program {
+    /// Compile Yarn files, given as their names and contents.
+    pub fn compile(files: impl IntoIterator<Item = (String, String)>) -> Result<Self, String> {
+        let files = files
+            .into_iter()
+            .map(|(file_name, source)| File { file_name, source })
+            .collect::<Vec<_>>();
+        let compilation = Compiler::new()
+            .add_files(files)
+            .compile()
+            .map_err(|error| error.to_string())?;
+        Ok(Self::from_compilation(&compilation))
+    }
+
+    /// The dialogue the game compiled.
+    pub fn from_compilation(compilation: &Compilation) -> Self {
+        let variables = compilation
+            .declarations
+            .iter()
+            .map(|declaration| Variable {
+                name: declaration.name.clone(),
+                initial_value: declaration.default_value.clone(),
+                file: match &declaration.source_file_name {
+                    DeclarationSource::File(file) => Some(file.clone()),
+                    _ => None,
+                },
+            })
+            .collect();
+        Self {
+            program: compilation.program.clone().unwrap_or_default(),
+            string_table: compilation
+                .string_table
+                .iter()
+                .map(|(id, info)| (id.clone(), info.text.clone()))
+                .collect(),
+            variables,
+        }
+    }
+
+    pub fn has_node(&self, title: &str) -> bool {
+        self.program.nodes.contains_key(title)
+    }
+
+    /// The value a variable has before the dialogue sets it.
+    pub fn initial_value(&self, name: &str) -> Option<YarnValue> {
+        self.variables
+            .iter()
+            .find(|variable| variable.name == name)
+            .and_then(|variable| variable.initial_value.clone())
+    }
+
+    /// Run a node on a state, returning every state it can end in. Every
+    /// option is taken, by running the node again for each choice.
+    ///
+    /// A node that starts again with the same variables loops. If options
+    /// were picked since, the other choices get out of the loop and the run
+    /// is dropped, otherwise it never ends and is an error.
+    pub fn run<S: YarnState>(&self, title: &str, state: S) -> Result<Vec<S>, String> {
+        let mut results = Vec::new();
+        // The choices to make, by index among the available options.
+        let mut pending = vec![Vec::new()];
+        'runs: while let Some(mut choices) = pending.pop() {
+            let mut state = state.clone();
+            let mut storage: Box<dyn VariableStorage> = Box::new(MemoryVariableStorage::new());
+            let mut text_provider = StringTableTextProvider::new();
+            text_provider.extend_base_language(self.string_table.clone());
+            let mut dialogue = Dialogue::new(storage.clone_shallow(), Box::new(text_provider));
+            dialogue.add_program(self.program.clone());
+            storage
+                .as_mut()
+                .extend(state.variables().clone().into_iter().collect())
+                .map_err(|error| error.to_string())?;
+            dialogue
+                .set_node(title)
+                .map_err(|error| error.to_string())?;
+
+            let mut chosen = 0;
+            // The nodes started in this run with the variables they started
+            // with, and how many options were picked by then.
+            let mut started = HashMap::new();
+            let mut complete = false;
+            while !complete {
+                let events = dialogue.continue_().map_err(|error| error.to_string())?;
+                for event in events {
+                    match event {
+                        DialogueEvent::Command(command) => {
+                            let args = command
+                                .parameters
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>();
+                            *state.variables() = read_variables(storage.as_ref());
+                            state
+                                .command(&command.name, &args)
+                                .map_err(|error| format!("{title}: {error}"))?;
+                            storage
+                                .as_mut()
+                                .extend(state.variables().clone().into_iter().collect())
+                                .map_err(|error| error.to_string())?;
+                        }
+                        DialogueEvent::Options(options) => {
+                            let available = options
+                                .iter()
+                                .filter(|option| option.is_available)
+                                .collect::<Vec<_>>();
+                            if available.is_empty() {
+                                return Err(format!("{title}: no option can be picked"));
+                            }
+                            if chosen == choices.len() {
+                                for other in 1..available.len() {
+                                    let mut other_choices = choices.clone();
+                                    other_choices.push(other);
+                                    pending.push(other_choices);
+                                }
+                                choices.push(0);
+                            }
+                            dialogue
+                                .set_selected_option(available[choices[chosen]].id)
+                                .map_err(|error| error.to_string())?;
+                            chosen += 1;
+                        }
+                        DialogueEvent::NodeStart(node) => {
+                            let variables = read_variables(storage.as_ref());
+                            match started.insert(format!("{node} {variables:?}"), chosen) {
+                                Some(before) if before == chosen => {
+                                    return Err(format!("{title}: {node} loops without end"));
+                                }
+                                Some(_) => continue 'runs,
+                                None => {}
+                            }
+                        }
+                        DialogueEvent::DialogueComplete => complete = true,
+                        _ => {}
+                    }
+                }
+            }
+            *state.variables() = read_variables(storage.as_ref());
+            results.push(state);
+        }
+        Ok(results)
+    }
+}
+
+/// The variables in the storage. How often a node was visited is capped at
+/// once, so that running a node again doesn't always make a new state.
+fn read_variables(storage: &dyn VariableStorage) -> BTreeMap<String, YarnValue> {
+    let visited = Library::generate_unique_visited_variable_for_node("");
+    storage
+        .variables()
+        .into_iter()
+        .map(|(name, value)| match value {
+            YarnValue::Number(count) if name.starts_with(&visited) => {
+                (name, YarnValue::Number(count.min(1.0)))
+            }
+            value => (name, value),
+        })
+        .collect()
+}
+
+/// The game state a [`Program`] runs on.
+pub trait YarnState: Clone {
+    /// The values of the variables. Variables that are missing start out
+    /// with their initial value.
+    fn variables(&mut self) -> &mut BTreeMap<String, YarnValue>;
+    /// Carry out a command, which may also change the variables.
+    fn command(&mut self, name: &str, args: &[String]) -> Result<(), String>;
+}
+
+/// A line of dialogue or an option, which is shown to the player.
+#[derive(Debug, Clone)]
+pub struct DialogueLine {
+    /// The `#line:` tag that string tables refer to the line by, e.g.
+    /// `line:wife_1`. Without one, Yarn Spinner makes up an id that changes
+    /// whenever the file does.
+    pub id: Option<String>,
+    pub text: String,
+    pub node: String,
+    /// Line in the file, starting at 1.
+    pub line: usize,
+}
+
+/// All lines of dialogue and options in a Yarn file, in order.
+pub fn dialogue_lines(source: &str) -> Vec<DialogueLine> {
+    let mut lines = Vec::new();
+    for (node, number, text) in body_lines(source) {
+        if text.starts_with("<<") {
+            continue;
+        }
+        let text = text.strip_prefix("->").unwrap_or(text);
+        let (text, tags) = text.split_once('#').unwrap_or((text, ""));
+        let id = tags
+            .split_whitespace()
+            .map(|tag| tag.trim_start_matches('#'))
+            .find(|tag| tag.starts_with("line:"))
+            .map(ToString::to_string);
+        lines.push(DialogueLine {
+            id,
+            text: text.trim().to_string(),
+            node: node.to_string(),
+            line: number,
+        });
+    }
+    lines
+}
+
+/// A `<<command>>` that the game has to handle.
+#[derive(Debug, Clone)]
+pub struct CommandLine {
+    pub name: String,
+    /// The arguments as written. Arguments with `{expressions}` in them are
+    /// only known when the command runs.
+    pub args: Vec<String>,
+    /// Line in the file, starting at 1.
+    pub line: usize,
+}
+
+/// Statements that Yarn Spinner handles itself.
+const KEYWORDS: &[&str] = &[
+    "if", "elseif", "else", "endif", "set", "declare", "jump", "stop",
+];
+
+/// All commands in a Yarn file, in order.
+pub fn commands(source: &str) -> Vec<CommandLine> {
+    let mut commands = Vec::new();
+    for (_, number, text) in body_lines(source) {
+        let Some(inner) = text
+            .strip_prefix("<<")
+            .and_then(|text| text.split_once(">>"))
+            .map(|(inner, _)| inner.trim())
+        else {
+            continue;
+        };
+        let (name, rest) = inner.split_once(' ').unwrap_or((inner, ""));
+        if KEYWORDS.contains(&name) {
+            continue;
+        }
+        commands.push(CommandLine {
+            name: name.to_string(),
+            args: split_args(rest),
+            line: number,
+        });
+    }
+    commands
+}
+
+/// The titles of all nodes in a Yarn file.
+pub fn node_titles(source: &str) -> Vec<String> {
+    let mut titles = Vec::new();
+    let mut in_body = false;
+    for line in source.lines() {
+        let text = line.trim();
+        if in_body {
+            in_body = text != "===";
+        } else if let Some(title) = text.strip_prefix("title:") {
+            titles.push(title.trim().to_string());
+        } else {
+            in_body = text == "---";
+        }
+    }
+    titles
+}
+
+/// The non-empty lines in node bodies that aren't comments, with the title
+/// of their node and their number, starting at 1.
+fn body_lines(source: &str) -> Vec<(&str, usize, &str)> {
+    let mut lines = Vec::new();
+    let mut node = "";
+    let mut in_body = false;
+    for (number, line) in source.lines().enumerate() {
+        let text = line.trim();
+        if !in_body {
+            if let Some(title) = text.strip_prefix("title:") {
+                node = title.trim();
+            }
+            in_body = text == "---";
+            continue;
+        }
+        if text == "===" {
+            in_body = false;
+            continue;
+        }
+        if text.is_empty() || text.starts_with("//") {
+            continue;
+        }
+        lines.push((node, number + 1, text));
+    }
+    lines
+}
+
+/// Split command arguments at spaces, keeping quoted arguments together.
+fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            args.push(quoted[..end].to_string());
+            rest = quoted.get(end + 1..).unwrap_or("").trim_start();
+        } else {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            args.push(rest[..end].to_string());
+            rest = rest[end..].trim_start();
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    struct TestState {
+        variables: BTreeMap<String, YarnValue>,
+        commands: Vec<String>,
+    }
+
+    impl YarnState for TestState {
+        fn variables(&mut self) -> &mut BTreeMap<String, YarnValue> {
+            &mut self.variables
+        }
+
+        fn command(&mut self, name: &str, args: &[String]) -> Result<(), String> {
+            if name == "fail" {
+                return Err("failed".to_string());
+            }
+            self.commands.push(format!("{name}{}", args.concat()));
+            Ok(())
+        }
+    }
+
+    fn run(source: &str) -> Result<Vec<Vec<String>>, String> {
+        let program = Program::compile([("test.yarn".to_string(), source.to_string())])?;
+        let mut results = program
+            .run("Start", TestState::default())?
+            .into_iter()
+            .map(|state| state.commands)
+            .collect::<Vec<_>>();
+        results.sort();
+        Ok(results)
+    }
+
+    #[test]
+    fn takes_every_option_and_follows_jumps() {
+        let source = "\
+title: Start
+---
+Which way?
+-> Left
+    <<go left>>
+-> Right
+    <<go right>>
+    <<jump End>>
+<<go back>>
+===
+title: End
+---
+<<go end>>
+===
+";
+        assert_eq!(
+            run(source).unwrap(),
+            [vec!["goleft", "goback"], vec!["goright", "goend"],]
+        );
+    }
+
+    #[test]
+    fn evaluates_comparisons_functions_and_interpolation() {
+        let source = "\
+title: Start
+---
+<<declare $coins = 2>>
+<<if $coins > 1 and $coins < 3>>
+    <<pay {$coins + 1}>>
+<<endif>>
+<<if string($coins) == \"2\">>
+    Thanks, {$coins} coins it is.
+    <<pay all>>
+<<endif>>
+===
+";
+        assert_eq!(run(source).unwrap(), [vec!["pay3", "payall"]]);
+    }
+
+    #[test]
+    fn skips_unavailable_options_and_reports_command_errors() {
+        let source = "\
+title: Start
+---
+-> Never <<if false>>
+    <<go never>>
+-> Always
+    <<fail>>
+===
+";
+        assert_eq!(run(source).unwrap_err(), "Start: failed");
+    }
+
+    #[test]
+    fn reports_endless_loops_and_leaves_loops_by_options() {
+        let endless = "\
+title: Start
+---
+Round and round.
+<<jump Start>>
+===
+";
+        assert_eq!(run(endless).unwrap_err(), "Start: Start loops without end");
+
+        let menu = "\
+title: Start
+---
+-> Again
+    <<jump Start>>
+-> Leave
+    <<go leave>>
+===
+";
+        assert_eq!(run(menu).unwrap(), [vec!["goleave"]]);
+    }
+
+    #[test]
+    fn variables_start_out_as_given() {
+        let source = "\
+title: Start
+---
+<<if $ready>>
+    <<go>>
+<<endif>>
+<<set $ready to false>>
+===
+";
+        let program = Program::compile([("test.yarn".to_string(), source.to_string())]).unwrap();
+        let mut state = TestState::default();
+        state
+            .variables
+            .insert("$ready".to_string(), YarnValue::Boolean(true));
+        let results = program.run("Start", state).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].commands, ["go"]);
+        assert_eq!(
+            results[0].variables.get("$ready"),
+            Some(&YarnValue::Boolean(false))
+        );
+    }
+
+    #[test]
+    fn lists_commands_with_their_lines() {
+        let source = "\
+title: Start
+---
+<<declare $seen = false>>
+<<if not $seen>>
+    <<say Wife \"Hello there\">>
+<<endif>>
+Text <<not a command>>
+<<end_game good>>
+===
+";
+        let commands = commands(source)
+            .into_iter()
+            .map(|command| (command.name, command.args, command.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commands,
+            [
+                (
+                    "say".to_string(),
+                    vec!["Wife".to_string(), "Hello there".to_string()],
+                    5
+                ),
+                ("end_game".to_string(), vec!["good".to_string()], 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_lines_and_nodes() {
+        let source = "\
+title: Start
+tags: intro
+---
+// Not a line.
+Hello! #line:start_1
+-> Bye #line:start_2
+===
+title: Other
+---
+===
+";
+        let lines = dialogue_lines(source);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].id.as_deref(), Some("line:start_1"));
+        assert_eq!(lines[0].text, "Hello!");
+        assert_eq!(lines[0].line, 5);
+        assert_eq!(lines[1].text, "Bye");
+        assert_eq!(lines[1].node, "Start");
+        assert_eq!(node_titles(source), ["Start", "Other"]);
+    }
+}
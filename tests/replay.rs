@@ -0,0 +1,24 @@
+//! Plays recorded runs back without a window and checks that they end in the
+//! state they were recorded in.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use thanks_wife::{AppPlugin, Replay};
+
+/// The caveman is told the intro, then walks right for a second.
+#[test]
+fn walk_replay_ends_as_recorded() {
+    let replay = Replay::load(Path::new("tests/replays/walk.ron")).unwrap();
+    assert!(replay.outcome.player_position.is_some());
+    assert!(replay.outcome.inventory.is_empty());
+    assert_eq!(replay.outcome.ending, None);
+
+    let exit = App::new()
+        .add_plugins(AppPlugin {
+            replay: Some(replay),
+            headless: true,
+        })
+        .run();
+    assert_eq!(exit, AppExit::Success);
+}